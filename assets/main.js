@@ -16,7 +16,7 @@ document.addEventListener("DOMContentLoaded", () => {
     const $selected_ips = document.querySelector("#selected_ips");
     const $selected_ips_body = $selected_ips.querySelector("tbody");
     const fill_selected_ips = async function() {
-        let response = await fetch("/api/ip/select");
+        let response = await fetch("/api/ip/results");
         let obj = await response.json();
         display_message(obj);
 
@@ -28,12 +28,12 @@ document.addEventListener("DOMContentLoaded", () => {
         $selected_ips_body.innerHTML = "";
 
         const fragment = document.createDocumentFragment();
-        for (let record of obj?.data) {
+        for (let record of obj?.data?.items) {
             let row = document.createElement("tr");
             row.appendChild(cell_cbx.cloneNode(true));
 
-            record?.splice(1, 2) // 跳过列：已发送、已接收
-            for (let field of record) {
+            // 显示列：IP、丢包率、延迟、下载速度
+            for (let field of [record.ip, record.loss, record.latency_ms, record.speed_mbps]) {
                 let cell = document.createElement("td");
                 cell.innerText = field;
                 row.appendChild(cell);
@@ -95,8 +95,11 @@ document.addEventListener("DOMContentLoaded", () => {
         show_loading_status();
 
         checking_status = true;
+        let version = null;
         for (let i = 0; i < 1000; i++) {
-            let response = await fetch("/api/ip/select/status");
+            // 首次立即获取状态，之后长轮询等待状态变化
+            let url = version === null ? "/api/ip/select/status" : `/api/ip/select/status?wait=30&since=${version}`;
+            let response = await fetch(url);
             let obj = await response.json();
             if (!obj || obj.code != 0) {
                 display_message(obj);
@@ -106,7 +109,15 @@ document.addEventListener("DOMContentLoaded", () => {
                 return;
             }
 
-            if (obj?.data == "Success" || obj?.data == "Pending") {
+            if (obj?.data?.status == "TimedOut" || obj?.data?.status == "Failed") {
+                display_message({ message: obj?.data?.job?.failure?.message });
+
+                hide_loading_status();
+                checking_status = false;
+                return;
+            }
+
+            if (obj?.data?.status == "Success" || obj?.data?.status == "Pending") {
                 fill_selected_ips();
 
                 hide_loading_status();
@@ -114,12 +125,10 @@ document.addEventListener("DOMContentLoaded", () => {
                 return;
             }
 
-            await sleep(2000);
+            version = obj?.data?.version;
         }
     }
 
-    const sleep = ms => new Promise(resolve => setTimeout(resolve, ms));
-
     // 异步检查状态并调用填充已优选IP表
     check_status();
 
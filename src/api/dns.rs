@@ -2,54 +2,764 @@
 //!
 //! 主要功能：
 //! - 查询DNS记录
-//! - 更新DNS记录
+//! - 更新DNS记录(指定IP，或取最近一次优选结果、稳定性排名中的第一个可用IP)
+//! - 记录值和TTL未变化时跳过更新，返回更新前后的值
+//! - 演练模式: 只返回变更计划，不修改记录
+//! - 记录每次DNS变更(时间、目标、变更前后的值、触发方式、发起方及结果)，支持回滚
+//! - 更新后可选地在后台查询DNS服务器，验证新值已生效，结果写入变更记录
+//! - 更新后验证IP可用性，验证失败时自动拉黑
+//! - 查询已发布IP的监控状态
+//! - 通过配置的DNS服务商(Namesilo、RFC 2136或Webhook)查询和更新记录
+//! - 将DNS服务商的业务错误转换为对应的响应码，并保留错误说明
 
 use axum::Json;
-use axum::extract::State;
-use serde::Deserialize;
+use axum::extract::{ConnectInfo, Path, Query, State};
+use axum::http::HeaderMap;
+use chrono::Local;
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
+use tracing::{error, info, warn};
 
-use crate::client::namesilo;
+use crate::api;
+use crate::client::namesilo::ResourceRecord;
+use crate::client::{dns, probe, provider};
+use crate::model::dns::{
+    ChangeOrigin, ChangeResult, DnsChange, Propagation, PropagationStatus, RecordValue, Trigger,
+};
+use crate::model::exclusion::ExclusionSource;
+use crate::model::monitor::MonitorReport;
 use crate::model::response;
-use crate::model::response::Resp;
+use crate::model::response::{Code, Resp};
 use crate::server::state::AppState;
 
+/// 手动变更时标识发起方的请求头
+const REQUESTER_HEADER: &str = "x-requester";
+
 /// 同步DNS记录
 ///
 /// # 参数
 /// - `state`: 应用状态，包含配置信息
-/// - `req`: 包含要更新的IP地址的请求
+/// - `addr`、`headers`: 客户端地址和请求头，用于记录变更发起方
+/// - `query`: 是否只演练(`dry_run=true`时只返回变更计划，不修改记录)及触发方式
+/// - `req`: 要更新的IP地址，或IP来源
 ///
 /// # 返回值
-/// - 成功: 返回成功响应
-/// - 失败: 返回错误响应
+/// - 成功: 返回同步结果(是否变化、更新前后的值及变更计划)
+/// - 失败: 返回错误响应(触发方式为failover时返回InvalidParameter)
 pub async fn sync(
     State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Query(query): Query<SyncQuery>,
     Json(req): Json<SyncRequest>,
-) -> anyhow::Result<Resp<()>, Resp<()>> {
+) -> anyhow::Result<Resp<SyncReply>, Resp<()>> {
+    // 故障切换只能由后台监控触发
+    let trigger = query.trigger.unwrap_or(Trigger::Manual);
+    if trigger == Trigger::Failover {
+        return Err(response::fail(
+            Code::InvalidParameter,
+            "trigger must be manual or schedule".to_string(),
+        ));
+    }
+    let ip: IpAddr = match req {
+        SyncRequest::Ip { ip } => ip
+            .parse()
+            .map_err(|_| response::fail(Code::InvalidParameter, format!("invalid ip: {}", ip)))?,
+        SyncRequest::From { from } => pick(&state, from).await?.ok_or_else(|| {
+            response::fail(Code::NotFound, "no candidate ip available".to_string())
+        })?,
+    };
+
     // 查找目标 DNS 记录
-    let records = namesilo::dns_list(&state.config.namesilo).await?;
-    let target_host = format!(
-        "{}.{}",
-        state.config.namesilo.rrhost, state.config.namesilo.domain
-    );
+    let rr = find_record(&state).await?;
+    if !rr.accepts(ip) {
+        return Err(response::fail(
+            Code::InvalidParameter,
+            format!(
+                "{} record {} cannot point to {}",
+                rr.record_type, rr.host, ip
+            ),
+        ));
+    }
+
+    // 更新 DNS 记录到新的IP地址
+    let origin = ChangeOrigin {
+        trigger,
+        requester: requester(&headers, addr),
+        rollback_of: None,
+    };
+    let ttl = provider::ttl(&state.config);
+    let reply = update_record(&state, &rr, ip, ttl, query.dry_run.unwrap_or(false), origin)
+        .await
+        .map_err(provider_error)?;
+
+    Ok(response::success_data(reply))
+}
 
-    // 在记录中查找匹配的目标主机
-    let rr = records
+/// 查询DNS变更记录
+///
+/// # 参数
+/// - `query`: 最多返回的记录数量(默认全部)
+///
+/// # 返回值
+/// - 成功: 返回变更记录，从新到旧排列
+pub async fn history(
+    State(state): State<AppState>,
+    Query(query): Query<HistoryQuery>,
+) -> anyhow::Result<Resp<Vec<DnsChange>>, Resp<()>> {
+    let changes = state
+        .dns_changes
+        .read()
+        .await
+        .changes
         .iter()
-        .find(|record| record.host == target_host)
-        .ok_or("target host not found")?;
+        .rev()
+        .take(query.limit.unwrap_or(usize::MAX))
+        .cloned()
+        .collect();
 
-    // 更新 DNS 记录到新的IP地址
-    namesilo::dns_update(&state.config.namesilo, &req.ip, &rr.record_id).await?;
+    Ok(response::success_data(changes))
+}
+
+/// 回滚DNS变更，将记录恢复为变更前的值和TTL
+///
+/// 只能回滚成功的变更，且记录当前的值必须仍是该次变更后的值，
+/// 否则说明之后又有变更，需要先回滚之后的变更
+///
+/// # 参数
+/// - `id`: 要回滚的变更ID
+/// - `addr`、`headers`: 客户端地址和请求头，用于记录变更发起方
+///
+/// # 返回值
+/// - 成功: 返回同步结果
+/// - 失败: 变更不存在、不可回滚或更新失败
+pub async fn rollback(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(id): Path<u64>,
+) -> anyhow::Result<Resp<SyncReply>, Resp<()>> {
+    let change = state
+        .dns_changes
+        .read()
+        .await
+        .changes
+        .iter()
+        .find(|change| change.id == id)
+        .cloned()
+        .ok_or_else(|| response::fail(Code::NotFound, format!("dns change {} not found", id)))?;
+    if change.result != ChangeResult::Success {
+        return Err(response::fail(
+            Code::InvalidParameter,
+            format!("dns change {} failed, nothing to roll back", id),
+        ));
+    }
+    if change.previous.value.is_empty() {
+        return Err(response::fail(
+            Code::InvalidParameter,
+            format!("dns change {} created the record, nothing to roll back", id),
+        ));
+    }
+    let ip: IpAddr = change.previous.value.parse().map_err(|_| {
+        response::fail(
+            Code::InvalidParameter,
+            format!(
+                "previous value {} of dns change {} is not an ip",
+                change.previous.value, id
+            ),
+        )
+    })?;
+
+    let rr = find_record(&state).await?;
+    if rr.host != change.host || rr.value != change.current.value {
+        return Err(response::fail(
+            Code::Conflict,
+            format!(
+                "record {} is now {}, changed since dns change {}",
+                rr.host, rr.value, id
+            ),
+        ));
+    }
+
+    let origin = ChangeOrigin {
+        trigger: Trigger::Manual,
+        requester: requester(&headers, addr),
+        rollback_of: Some(id),
+    };
+    let reply = update_record(&state, &rr, ip, change.previous.ttl, false, origin)
+        .await
+        .map_err(provider_error)?;
+
+    Ok(response::success_data(reply))
+}
+
+/// 查找目标DNS记录
+///
+/// 记录不存在时，更新时可以创建记录的服务商(RFC 2136)返回值为空的记录
+///
+/// # 返回值
+/// - 成功: 返回目标记录
+/// - 失败: 调用DNS服务商失败或记录不存在
+async fn find_record(state: &AppState) -> anyhow::Result<ResourceRecord, Resp<()>> {
+    provider::find(&state.config)
+        .await
+        .map_err(provider_error)?
+        .or_else(|| provider::missing_record(&state.config))
+        .ok_or_else(|| {
+            response::fail(
+                Code::NotFound,
+                format!("record {} not found", provider::target_host(&state.config)),
+            )
+        })
+}
+
+/// 获取手动变更的发起方
+///
+/// 优先使用请求头`X-Requester`，未设置时使用客户端地址
+fn requester(headers: &HeaderMap, addr: SocketAddr) -> String {
+    headers
+        .get(REQUESTER_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map_or_else(|| addr.ip().to_string(), str::to_string)
+}
 
-    Ok(response::success())
+/// 将DNS服务商调用错误转换为响应
+///
+/// DNS服务商的业务错误(Namesilo返回码、RFC 2136响应码、Webhook接口HTTP状态)转换为对应的响应码，
+/// 响应信息中保留服务商的错误说明，其余错误为内部错误
+fn provider_error(e: anyhow::Error) -> Resp<()> {
+    match provider::error_code(&e) {
+        Some(code) => response::fail(code, e.to_string()),
+        None => e.into(),
+    }
 }
 
-/// DNS同步请求结构体
+/// 从指定来源选取第一个不在排除列表中的IP
+///
+/// # 参数
+/// - `state`: 应用状态
+/// - `from`: IP来源
+///
+/// # 返回值
+/// - 成功: 返回选中的IP，没有可用IP时返回None
+/// - 失败: 读取优选结果失败
+async fn pick(state: &AppState, from: SyncSource) -> anyhow::Result<Option<IpAddr>> {
+    let candidates: Vec<String> = match from {
+        SyncSource::Latest => api::ip::latest_results(state)
+            .await?
+            .into_iter()
+            .map(|result| result.ip)
+            .collect(),
+        SyncSource::Ranking => api::job::build_ranking(state, state.config.history.ranking_jobs)
+            .await?
+            .items
+            .into_iter()
+            .map(|item| item.ip)
+            .collect(),
+    };
+
+    let exclusions = api::exclusion::active(state).await;
+    Ok(candidates
+        .iter()
+        .filter_map(|ip| ip.parse::<IpAddr>().ok())
+        .find(|ip| !exclusions.iter().any(|net| net.contains(ip))))
+}
+
+/// 将目标DNS记录更新为指定IP
+///
+/// 手动同步、回滚和故障切换共用。先生成变更计划并记录日志，记录值和TTL都未变化或
+/// 只演练时不调用更新接口。调用更新接口后无论成败都写入变更记录。更新成功后按配置
+/// 在后台等待新值在DNS服务器上生效(不阻塞调用方，结果写入变更记录)，并在后台验证新IP
+///
+/// # 参数
+/// - `state`: 应用状态
+/// - `record`: 目标记录(更新前)
+/// - `ip`: 新的IP
+/// - `ttl`: 新的TTL
+/// - `dry_run`: 是否只生成变更计划
+/// - `origin`: 变更来源
+///
+/// # 返回值
+/// - 成功: 返回同步结果
+/// - 失败: 记录类型与IP不匹配或更新失败
+pub async fn update_record(
+    state: &AppState,
+    record: &ResourceRecord,
+    ip: IpAddr,
+    ttl: u32,
+    dry_run: bool,
+    origin: ChangeOrigin,
+) -> anyhow::Result<SyncReply> {
+    if !record.accepts(ip) {
+        return Err(anyhow::anyhow!(
+            "{} record {} cannot point to {}",
+            record.record_type,
+            record.host,
+            ip
+        ));
+    }
+
+    let mut reply = SyncReply::plan(record, ip, ttl);
+    info!("dns sync plan: {}", reply.plan);
+    if reply.status == SyncStatus::Unchanged || dry_run {
+        return Ok(reply);
+    }
+
+    let result = provider::update(&state.config, record, ip, ttl).await;
+    if result.is_ok() {
+        info!(
+            "dns record {} updated from {} (ttl {}) to {} (ttl {})",
+            record.host, record.value, record.ttl, ip, reply.current.ttl
+        );
+        reply.status = SyncStatus::Updated;
+        if state.config.propagation.enabled {
+            reply.propagation = Some(Propagation::pending());
+        }
+    }
+    reply.change_id = audit(state, &reply, origin, &result).await;
+    result?;
+
+    if reply.propagation.is_some() {
+        tokio::spawn(verify_propagation(
+            state.clone(),
+            reply.change_id,
+            reply.host.clone(),
+            reply.record_type.clone(),
+            ip,
+        ));
+    }
+
+    // 在后台验证新IP，验证失败时自动拉黑
+    if state.config.exclusion.auto_block {
+        tokio::spawn(verify(state.clone(), ip));
+    }
+
+    Ok(reply)
+}
+
+/// 获取已发布IP的监控状态
+///
+/// # 返回值
+/// - 成功: 返回监控状态、探测历史及当前IP的统计
+pub async fn monitor(
+    State(state): State<AppState>,
+) -> anyhow::Result<Resp<MonitorReport>, Resp<()>> {
+    let monitor = state.monitor.read().await.clone();
+    let summary = monitor.summary();
+
+    Ok(response::success_data(MonitorReport {
+        state: monitor,
+        summary,
+    }))
+}
+
+/// 查询DNS服务器，等待新值生效，并将结果写入变更记录
+///
+/// # 参数
+/// - `state`: 应用状态
+/// - `id`: 变更ID(变更记录写入失败时为None，只记录日志)
+/// - `host`: 记录主机名
+/// - `record_type`: 记录类型
+/// - `ip`: 新的IP
+async fn verify_propagation(
+    state: AppState,
+    id: Option<u64>,
+    host: String,
+    record_type: String,
+    ip: IpAddr,
+) {
+    let propagation =
+        dns::wait_propagation(&state.config.propagation, &host, &record_type, ip).await;
+    match propagation.status {
+        PropagationStatus::Propagated => info!(
+            "dns record {} propagated in {} ms",
+            host, propagation.elapsed_ms
+        ),
+        PropagationStatus::TimedOut => warn!(
+            "dns record {} not propagated after {} ms: {:?}",
+            host, propagation.elapsed_ms, propagation.servers
+        ),
+        PropagationStatus::Failed | PropagationStatus::Pending => warn!(
+            "dns record {} propagation not verified: {}",
+            host,
+            propagation.error.as_deref().unwrap_or_default()
+        ),
+    }
+
+    let Some(id) = id else {
+        return;
+    };
+    // 变更记录可能已被清理
+    let written = state
+        .dns_changes
+        .update(|log| {
+            if let Some(change) = log.changes.iter_mut().find(|change| change.id == id) {
+                change.propagation = Some(propagation);
+            }
+        })
+        .await;
+    if let Err(e) = written {
+        error!("Failed to record propagation of dns change {}: {}", id, e);
+    }
+}
+
+/// 写入DNS变更记录
+///
+/// 变更ID由持久化的计数器分配，删除旧记录后也不会复用。超出保留数量时删除最早的记录，
+/// 写入失败只记录日志，不影响已完成的变更
+///
+/// # 参数
+/// - `state`: 应用状态
+/// - `reply`: 变更计划
+/// - `origin`: 变更来源
+/// - `result`: 调用DNS服务商的结果
+///
+/// # 返回值
+/// 返回变更ID，写入失败时返回None
+async fn audit(
+    state: &AppState,
+    reply: &SyncReply,
+    origin: ChangeOrigin,
+    result: &anyhow::Result<()>,
+) -> Option<u64> {
+    let capacity = state.config.history.dns_changes;
+    let written = state
+        .dns_changes
+        .update(|log| {
+            log.last_id += 1;
+            let id = log.last_id;
+            log.changes.push(DnsChange {
+                id,
+                changed_at: Local::now(),
+                host: reply.host.clone(),
+                record_type: reply.record_type.clone(),
+                previous: reply.previous.clone(),
+                current: reply.current.clone(),
+                trigger: origin.trigger,
+                requester: origin.requester,
+                rollback_of: origin.rollback_of,
+                result: match result {
+                    Ok(()) => ChangeResult::Success,
+                    Err(_) => ChangeResult::Failed,
+                },
+                error: result.as_ref().err().map(|e| e.to_string()),
+                propagation: reply.propagation.clone(),
+            });
+            let overflow = log.changes.len().saturating_sub(capacity);
+            log.changes.drain(..overflow);
+            id
+        })
+        .await;
+
+    match written {
+        Ok(id) => {
+            info!("dns change {} recorded", id);
+            Some(id)
+        }
+        Err(e) => {
+            error!("Failed to record dns change: {}", e);
+            None
+        }
+    }
+}
+
+/// 验证同步到DNS的IP是否可用
+///
+/// 所有探测连接都失败时，将该IP加入排除列表，有效期由配置决定
+///
+/// # 参数
+/// - `state`: 应用状态
+/// - `ip`: 刚同步的IP
+async fn verify(state: AppState, ip: IpAddr) {
+    let result = probe::tcp_probe(&state.config.probe, ip).await;
+    if !result.is_unreachable() {
+        info!("verified {}: {:?}", ip, result);
+        return;
+    }
+
+    warn!("verification failed for {}: {:?}", ip, result);
+    let Some(ttl) = api::exclusion::ttl(state.config.exclusion.block_ttl_secs) else {
+        error!(
+            "cannot block {}: invalid exclusion.block_ttl_secs {}",
+            ip, state.config.exclusion.block_ttl_secs
+        );
+        return;
+    };
+    let reason = format!(
+        "verification failed after dns sync ({}/{} connections failed)",
+        result.sent - result.received,
+        result.sent
+    );
+    if let Err(e) = api::exclusion::block(
+        &state,
+        IpNet::from(ip),
+        reason,
+        ExclusionSource::AutoBlock,
+        Some(ttl),
+    )
+    .await
+    {
+        error!("Failed to block {}: {}", ip, e);
+    }
+}
+
+/// DNS同步查询参数
 ///
 /// # 字段
-/// - `ip`: 要更新的IP地址
+/// - `dry_run`: 是否只返回变更计划，不修改记录
+/// - `trigger`: 触发方式(默认manual，定时任务调用时为schedule)
 #[derive(Deserialize)]
-pub struct SyncRequest {
-    pub ip: String,
+pub struct SyncQuery {
+    pub dry_run: Option<bool>,
+    pub trigger: Option<Trigger>,
+}
+
+/// DNS变更记录查询参数
+///
+/// # 字段
+/// - `limit`: 最多返回的记录数量
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<usize>,
+}
+
+/// DNS同步请求
+///
+/// 兼容以下格式:
+/// - 指定IP: `{"ip": "1.1.1.1"}`
+/// - 指定来源: `{"from": "latest"}` 或 `{"from": "ranking"}`
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum SyncRequest {
+    /// 要更新的IP地址
+    Ip { ip: String },
+    /// 从优选结果中选取IP
+    From { from: SyncSource },
+}
+
+/// DNS同步的IP来源
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncSource {
+    /// 最近一次成功任务的结果
+    Latest,
+    /// 最近多次成功任务的稳定性排名
+    Ranking,
+}
+
+/// DNS同步结果
+///
+/// # 字段
+/// - `status`: 是否实际更新了记录
+/// - `host`: 记录主机名
+/// - `record_type`: 记录类型
+/// - `previous`: 更新前的记录值
+/// - `current`: 更新后的记录值
+/// - `plan`: 变更计划说明
+/// - `change_id`: 变更记录ID(调用了更新接口时才有)
+/// - `propagation`: DNS传播验证状态(开启验证且已更新时才有，验证在后台进行，结果写入变更记录)
+#[derive(Debug, Serialize)]
+pub struct SyncReply {
+    pub status: SyncStatus,
+    pub host: String,
+    pub record_type: String,
+    pub previous: RecordValue,
+    pub current: RecordValue,
+    pub plan: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub propagation: Option<Propagation>,
+}
+
+impl SyncReply {
+    /// 生成将记录更新为指定IP的变更计划
+    ///
+    /// 记录值和TTL都未变化时状态为`Unchanged`，否则为`Planned`。记录值为空表示记录不存在，
+    /// 计划为创建记录
+    ///
+    /// # 参数
+    /// - `record`: 目标记录(更新前)
+    /// - `ip`: 新的IP
+    /// - `ttl`: 新的TTL
+    pub fn plan(record: &ResourceRecord, ip: IpAddr, ttl: u32) -> Self {
+        let unchanged = record.value.parse::<IpAddr>().ok() == Some(ip) && record.ttl == ttl;
+        let (status, plan) = if unchanged {
+            (
+                SyncStatus::Unchanged,
+                format!(
+                    "{} {} already points to {}, TTL {}, nothing to change",
+                    record.host, record.record_type, ip, ttl
+                ),
+            )
+        } else if record.value.is_empty() {
+            (
+                SyncStatus::Planned,
+                format!(
+                    "would create {} {} pointing to {}, TTL {}",
+                    record.host, record.record_type, ip, ttl
+                ),
+            )
+        } else {
+            let ttl_change = if record.ttl == ttl {
+                ttl.to_string()
+            } else {
+                format!("{} to {}", record.ttl, ttl)
+            };
+            (
+                SyncStatus::Planned,
+                format!(
+                    "would change {} {} from {} to {}, TTL {}",
+                    record.host, record.record_type, record.value, ip, ttl_change
+                ),
+            )
+        };
+
+        Self {
+            status,
+            host: record.host.clone(),
+            record_type: record.record_type.clone(),
+            previous: RecordValue {
+                value: record.value.clone(),
+                ttl: record.ttl,
+            },
+            current: RecordValue {
+                value: ip.to_string(),
+                ttl,
+            },
+            plan,
+            change_id: None,
+            propagation: None,
+        }
+    }
+}
+
+/// DNS同步状态
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStatus {
+    /// 已更新
+    Updated,
+    /// 记录值和TTL未变化，跳过更新
+    Unchanged,
+    /// 演练模式，记录需要更新但未修改
+    Planned,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::namesilo;
+    use crate::configure::AppConfig;
+    use std::time::{Duration, Instant};
+    use tokio::net::UdpSocket;
+
+    fn record(value: &str, ttl: u32) -> ResourceRecord {
+        ResourceRecord {
+            record_id: "abc".to_string(),
+            record_type: "A".to_string(),
+            host: "t.example.xyz".to_string(),
+            value: value.to_string(),
+            ttl,
+        }
+    }
+
+    /// 测试变更计划的状态和说明
+    #[test]
+    fn test_plan() {
+        let ip = "104.16.1.2".parse().unwrap();
+
+        let reply = SyncReply::plan(&record("104.16.1.1", 7207), ip, 7207);
+        assert_eq!(reply.status, SyncStatus::Planned);
+        assert_eq!(
+            reply.plan,
+            "would change t.example.xyz A from 104.16.1.1 to 104.16.1.2, TTL 7207"
+        );
+
+        let reply = SyncReply::plan(&record("104.16.1.2", 3600), ip, 7207);
+        assert_eq!(reply.status, SyncStatus::Planned);
+        assert_eq!(
+            reply.plan,
+            "would change t.example.xyz A from 104.16.1.2 to 104.16.1.2, TTL 3600 to 7207"
+        );
+
+        let reply = SyncReply::plan(&record("104.16.1.2", 7207), ip, 7207);
+        assert_eq!(reply.status, SyncStatus::Unchanged);
+        assert_eq!(reply.previous.value, reply.current.value);
+
+        let reply = SyncReply::plan(&record("", 0), ip, 300);
+        assert_eq!(reply.status, SyncStatus::Planned);
+        assert_eq!(
+            reply.plan,
+            "would create t.example.xyz A pointing to 104.16.1.2, TTL 300"
+        );
+    }
+
+    /// 测试变更发起方优先使用请求头，未设置时使用客户端地址
+    #[test]
+    fn test_requester() {
+        let addr: SocketAddr = "192.168.1.10:50000".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        assert_eq!(requester(&headers, addr), "192.168.1.10");
+
+        headers.insert(REQUESTER_HEADER, " ops ".parse().unwrap());
+        assert_eq!(requester(&headers, addr), "ops");
+    }
+
+    /// 测试传播验证在后台进行，同步立即返回，验证结果写入变更记录
+    #[tokio::test]
+    async fn test_propagation_in_background() {
+        // 不响应查询的DNS服务器
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let server = silent.local_addr().unwrap();
+
+        let (namesilo, _) =
+            namesilo::tests::serve(vec![(200, r#"{"reply":{"code":300,"detail":"success"}}"#)])
+                .await;
+        let mut config = AppConfig::test(
+            "[propagation]\nenabled = true\ntimeout_ms = 200\ninterval_ms = 10\ndeadline_secs = 1\n\
+             [exclusion]\nauto_block = false",
+        );
+        config.namesilo = namesilo;
+        config.propagation.nameservers = vec![server.to_string()];
+        let state = AppState::new(config).await.unwrap();
+
+        let origin = ChangeOrigin {
+            trigger: Trigger::Manual,
+            requester: "test".to_string(),
+            rollback_of: None,
+        };
+        let started = Instant::now();
+        let ip = "104.16.1.2".parse().unwrap();
+        let reply = update_record(&state, &record("104.16.1.1", 7207), ip, 7207, false, origin)
+            .await
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(reply.status, SyncStatus::Updated);
+        assert_eq!(reply.change_id, Some(1));
+        assert_eq!(
+            reply.propagation.unwrap().status,
+            PropagationStatus::Pending
+        );
+
+        let status = || async {
+            state.dns_changes.read().await.changes[0]
+                .propagation
+                .as_ref()
+                .unwrap()
+                .status
+        };
+        assert_eq!(status().await, PropagationStatus::Pending);
+        for _ in 0..50 {
+            if status().await != PropagationStatus::Pending {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert_eq!(status().await, PropagationStatus::TimedOut);
+        drop(silent);
+    }
 }
@@ -0,0 +1,219 @@
+//! IP排除列表API模块
+//!
+//! 维护优选时需要跳过的IP和IP段：
+//! - 查询当前有效的排除项
+//! - 手动添加、移除排除项
+//! - 供DNS同步在验证失败时自动拉黑IP
+
+use crate::catalog;
+use crate::model::exclusion::{Exclusion, ExclusionSource};
+use crate::model::response;
+use crate::model::response::{Code, Resp};
+use crate::server::state::AppState;
+use anyhow::Context;
+use axum::Json;
+use axum::extract::{Query, State};
+use chrono::{Duration, Local};
+use ipnet::IpNet;
+use serde::Deserialize;
+use tracing::info;
+
+/// 获取当前有效的排除项
+///
+/// # 返回值
+/// - 成功: 返回未过期的排除项列表
+pub async fn list(State(state): State<AppState>) -> anyhow::Result<Resp<Vec<Exclusion>>, Resp<()>> {
+    let now = Local::now();
+    let exclusions = state
+        .exclusions
+        .read()
+        .await
+        .iter()
+        .filter(|exclusion| exclusion.is_active(now))
+        .cloned()
+        .collect();
+
+    Ok(response::success_data(exclusions))
+}
+
+/// 添加排除项
+///
+/// 相同IP段已存在时会被覆盖
+///
+/// # 参数
+/// - `req`: 排除的IP或IP段、原因及有效期
+///
+/// # 返回值
+/// - 成功: 返回添加的排除项
+/// - 失败: IP段格式错误或有效期不为正数、超出范围
+pub async fn add(
+    State(state): State<AppState>,
+    Json(req): Json<AddRequest>,
+) -> anyhow::Result<Resp<Exclusion>, Resp<()>> {
+    let target = catalog::parse_range(&req.target)
+        .map_err(|e| response::fail(Code::InvalidParameter, e.to_string()))?;
+    let ttl = match req.ttl_secs {
+        Some(secs) => Some(ttl(secs).ok_or_else(|| {
+            response::fail(
+                Code::InvalidParameter,
+                format!("invalid ttl_secs: {}", secs),
+            )
+        })?),
+        None => None,
+    };
+
+    let exclusion = block(
+        &state,
+        target,
+        req.reason.unwrap_or_default(),
+        ExclusionSource::Manual,
+        ttl,
+    )
+    .await?;
+
+    Ok(response::success_data(exclusion))
+}
+
+/// 移除排除项
+///
+/// # 参数
+/// - `req`: 要移除的IP或IP段
+///
+/// # 返回值
+/// - 成功: 返回成功响应
+/// - 失败: 格式错误或排除项不存在
+pub async fn remove(
+    State(state): State<AppState>,
+    Query(req): Query<RemoveRequest>,
+) -> anyhow::Result<Resp<()>, Resp<()>> {
+    let target = catalog::parse_range(&req.target)
+        .map_err(|e| response::fail(Code::InvalidParameter, e.to_string()))?
+        .trunc();
+
+    let removed = state
+        .exclusions
+        .update(|exclusions| {
+            let len = exclusions.len();
+            exclusions.retain(|exclusion| exclusion.target != target);
+            exclusions.len() != len
+        })
+        .await?;
+    if !removed {
+        return Err(response::fail(
+            Code::NotFound,
+            format!("exclusion not found: {}", target),
+        ));
+    }
+
+    info!("exclusion removed: {}", target);
+    Ok(response::success())
+}
+
+/// 将IP段加入排除列表
+///
+/// 同时清理已过期的排除项，相同IP段的旧排除项会被替换
+///
+/// # 参数
+/// - `state`: 应用状态
+/// - `target`: 排除的IP段
+/// - `reason`: 排除原因
+/// - `source`: 排除来源
+/// - `ttl`: 有效期，为空表示永久有效
+///
+/// # 返回值
+/// - 成功: 返回新的排除项
+/// - 失败: 有效期超出范围或写入存储失败
+pub async fn block(
+    state: &AppState,
+    target: IpNet,
+    reason: String,
+    source: ExclusionSource,
+    ttl: Option<Duration>,
+) -> anyhow::Result<Exclusion> {
+    let now = Local::now();
+    let exclusion = Exclusion {
+        target: target.trunc(),
+        reason,
+        source,
+        created_at: now,
+        expires_at: ttl
+            .map(|ttl| {
+                now.checked_add_signed(ttl)
+                    .with_context(|| format!("exclusion ttl out of range: {}", ttl))
+            })
+            .transpose()?,
+    };
+
+    state
+        .exclusions
+        .update(|exclusions| {
+            exclusions.retain(|e| e.is_active(now) && e.target != exclusion.target);
+            exclusions.push(exclusion.clone());
+        })
+        .await?;
+
+    info!(
+        "exclusion added: {} ({:?}, expires at {:?})",
+        exclusion.target, exclusion.source, exclusion.expires_at
+    );
+    Ok(exclusion)
+}
+
+/// 将有效期秒数转换为时长
+///
+/// # 返回值
+/// 秒数不为正数或到期时间超出范围时返回None
+pub fn ttl(secs: i64) -> Option<Duration> {
+    let ttl = Duration::try_seconds(secs).filter(|_| secs > 0)?;
+    Local::now().checked_add_signed(ttl).map(|_| ttl)
+}
+
+/// 获取当前有效的排除IP段
+pub async fn active(state: &AppState) -> Vec<IpNet> {
+    let now = Local::now();
+    state
+        .exclusions
+        .read()
+        .await
+        .iter()
+        .filter(|exclusion| exclusion.is_active(now))
+        .map(|exclusion| exclusion.target)
+        .collect()
+}
+
+/// 添加排除项请求
+///
+/// # 字段
+/// - `target`: IP或IP段
+/// - `reason`: 排除原因(可选)
+/// - `ttl_secs`: 有效期(秒，可选，必须为正数，为空表示永久有效)
+#[derive(Deserialize)]
+pub struct AddRequest {
+    pub target: String,
+    pub reason: Option<String>,
+    pub ttl_secs: Option<i64>,
+}
+
+/// 移除排除项请求
+///
+/// # 字段
+/// - `target`: IP或IP段
+#[derive(Deserialize)]
+pub struct RemoveRequest {
+    pub target: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试有效期必须为正数且到期时间不能超出范围
+    #[test]
+    fn test_ttl() {
+        assert_eq!(ttl(60), Some(Duration::seconds(60)));
+        assert_eq!(ttl(0), None);
+        assert_eq!(ttl(-60), None);
+        assert_eq!(ttl(i64::MAX / 1000), None);
+        assert_eq!(ttl(i64::MAX), None);
+    }
+}
@@ -4,125 +4,1289 @@
 //! - 批量IP测试与优选(/select)
 //! - 查询优选任务状态(/status)
 //! - 获取优选结果IP列表(/selected)
+//! - 获取最优IP，支持纯文本格式(/best)
+//! - 导出优选结果为CSV、NDJSON或Prometheus文本(/export)
+//! - 导入其他机器上的CloudflareSpeedTest结果文件(/import)
+//! - 查询与刷新内置Cloudflare IP段(/ranges)
 //!
 //! # 实现原理
-//! 1. 使用CloudflareSpeedTest工具进行IP延迟测试
-//! 2. 通过异步任务执行耗时操作
-//! 3. 使用全局状态锁跟踪任务进度
+//! 1. 展开IP段并剔除排除列表中的IP
+//! 2. 使用CloudflareSpeedTest工具进行IP延迟测试
+//! 3. 通过异步任务执行耗时操作
+//! 4. 使用任务状态机跟踪任务进度，保证同一时间只有一个任务执行
+//! 5. 任务进行中时新的请求进入有界队列，按顺序执行
+//! 6. 任务超过配置的时长后终止子进程，标记为超时
+//! 7. 任务结束后保存任务信息和结果文件，查询结果时读取最近一次成功任务的结果
 
+use crate::api;
+use crate::catalog;
+use crate::catalog::{Catalog, Family};
+use crate::client::cloudflare;
 use crate::model::response;
-use crate::model::response::Resp;
-use crate::model::select::Status;
+use crate::model::response::{Code, Resp};
+use crate::model::select::{Failure, FailureKind, IpResult, JobInfo, SelectStatus, Status};
+use crate::server::job::Submit;
+use crate::server::state::AppState;
 use axum::Json;
-use std::sync::{LazyLock, RwLock};
+use axum::body::Body;
+use axum::extract::{Multipart, Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use futures_util::stream;
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use std::iter;
+use std::net::IpAddr;
+use std::process::Output;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::fs;
 use tokio::process::Command;
+use tokio::time;
 use tracing::*;
 
-/// 全局IP优选任务状态锁
-///
-/// 使用`LazyLock`实现懒加载，`RwLock`保证线程安全
-///
-/// # 状态说明
-/// - `Pending`: 初始状态，无进行中的优选任务
-/// - `Processing`: 优选任务进行中
-/// - `Success`: 优选任务成功完成
-/// - `Failed`: 优选任务失败，包含错误信息
-pub static STATUS: LazyLock<RwLock<Status>> = LazyLock::new(|| RwLock::new(Status::Pending));
-
 /// 启动IP选择任务
 ///
 /// # 参数
-/// - `req`: 要测试的IP地址列表(JSON格式)
+/// - `state`: 应用状态，包含内置IP段目录和IP段预设
+/// - `req`: 要测试的IP段(JSON格式)
 ///
 /// # 返回值
-/// - 成功: 返回成功响应(立即返回，实际处理在后台进行)
-/// - 失败: 返回错误响应
+/// - 成功: 返回任务在队列中的位置(立即返回，实际处理在后台进行)
+/// - 失败: 参数错误或队列已满
 pub async fn select(
-    Json(req): Json<Vec<String>>,
-) -> anyhow::Result<Resp<Vec<Vec<String>>>, Resp<()>> {
-    // 检查当前状态，避免重复执行优选任务
-    if let Status::Processing = STATUS.read()?.clone() {
-        return Ok(response::success());
+    State(state): State<AppState>,
+    Json(req): Json<SelectRequest>,
+) -> anyhow::Result<Resp<SelectReply>, Resp<()>> {
+    // 解析出实际要测试的IP段
+    let req = match req {
+        SelectRequest::List(ranges)
+        | SelectRequest::Options {
+            ranges: Ranges::List(ranges),
+        } => ranges,
+        SelectRequest::Options {
+            ranges: Ranges::Builtin(family),
+        } => state.catalog.read().await.ranges(family.into()),
+        SelectRequest::Preset { preset } => api::preset::resolve(&state, &preset).await?,
+    };
+
+    // 展开IP段并剔除排除列表中的IP
+    let exclusions = api::exclusion::active(&state).await;
+    let nets = expand_ranges(&req, &exclusions)
+        .map_err(|e| response::fail(Code::InvalidParameter, e.to_string()))?;
+    let ips = count_ips(&nets);
+    let req: Vec<String> = nets.iter().map(IpNet::to_string).collect();
+    if req.is_empty() {
+        return Err(response::fail(
+            Code::InvalidParameter,
+            "no ip range left to select".to_string(),
+        ));
     }
 
-    // 获取写锁并更新状态为PROCESSING
-    *STATUS.write()? = Status::Processing;
+    // 通过状态机原子地启动任务或进入队列
+    let (job, position) = match state.jobs.submit(req.clone(), ips) {
+        Submit::Started(job) => {
+            info!("select job {} started", job.id);
+            // 在后台异步执行优选任务
+            tokio::spawn(run(state, job.clone(), req));
+            (job, 0)
+        }
+        Submit::Queued(job, position) => {
+            info!("select job {} queued at position {}", job.id, position);
+            (job, position)
+        }
+        Submit::Busy => {
+            return Err(response::fail(
+                Code::Busy,
+                format!("job queue is full ({})", state.config.job.queue_depth),
+            ));
+        }
+    };
 
-    // 在后台异步执行优选任务
-    tokio::spawn(async move {
-        let result = _select(req).await;
-        let status = STATUS.write();
-        if let Err(e) = status {
-            error!("Failed to acquire lock: {}", e);
-            return;
+    Ok(response::success_data(SelectReply {
+        id: job.id,
+        position,
+    }))
+}
+
+/// 依次执行优选任务，直到队列为空
+///
+/// 每个任务结束后先保存到任务历史，再更新任务状态，
+/// 保证状态变为成功时已能查询到该任务的结果
+///
+/// # 参数
+/// - `state`: 应用状态，包含任务状态机和任务历史
+/// - `job`: 第一个任务
+/// - `ranges`: 第一个任务要测试的IP段
+async fn run(state: AppState, mut job: JobInfo, mut ranges: Vec<String>) {
+    let timeout = state.config.job.timeout();
+    loop {
+        let results = match _select(ranges, timeout).await {
+            Ok((results, text)) => {
+                job.finish(Ok(results.len()));
+                Some((results, text))
+            }
+            Err(failure) => {
+                error!("select job {} failed: {:?}", job.id, failure);
+                job.finish(Err(failure));
+                None
+            }
+        };
+
+        if let Err(e) = state
+            .job_history
+            .record(job.clone(), results.as_ref().map(|(_, text)| text.as_str()))
+            .await
+        {
+            error!("Failed to record select job {}: {}", job.id, e);
+        }
+        if let Some((results, _)) = &results {
+            api::job::record_measurements(&state, &job, results).await;
         }
 
-        // 处理完成后更新状态
-        if let Err(e) = result {
-            *status.unwrap() = Status::Failed(e.to_string());
-            return;
+        // 队列中还有任务时保持PROCESSING状态继续执行
+        match state.jobs.finish(job) {
+            Some(next) => {
+                info!("select job {} dequeued", next.info.id);
+                job = next.info;
+                ranges = next.ranges;
+            }
+            None => return,
         }
+    }
+}
 
-        *status.unwrap() = Status::Success;
-    });
+/// 启动IP选择任务的响应
+///
+/// # 字段
+/// - `id`: 任务ID
+/// - `position`: 任务在队列中的位置，0表示已立即开始执行
+#[derive(Serialize)]
+pub struct SelectReply {
+    pub id: u64,
+    pub position: usize,
+}
+
+/// IP选择请求
+///
+/// 兼容以下格式:
+/// - 直接传入IP段列表: `["1.1.1.0/24", ...]`
+/// - 对象格式: `{"ranges": "builtin"}` 或 `{"ranges": ["1.1.1.0/24", ...]}`
+/// - 引用预设: `{"preset": "office"}`
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum SelectRequest {
+    /// IP段列表
+    List(Vec<String>),
+    /// 对象格式
+    Options { ranges: Ranges },
+    /// 引用服务端保存的预设
+    Preset { preset: String },
+}
+
+/// IP段来源
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Ranges {
+    /// 使用内置IP段目录
+    Builtin(Builtin),
+    /// 自定义IP段列表
+    List(Vec<String>),
+}
+
+/// 内置IP段目录选项
+#[derive(Deserialize, Clone, Copy)]
+pub enum Builtin {
+    /// IPv4和IPv6全部IP段
+    #[serde(rename = "builtin")]
+    All,
+    /// 仅IPv4段
+    #[serde(rename = "builtin_v4")]
+    V4,
+    /// 仅IPv6段
+    #[serde(rename = "builtin_v6")]
+    V6,
+}
+
+impl From<Builtin> for Family {
+    fn from(builtin: Builtin) -> Self {
+        match builtin {
+            Builtin::All => Family::All,
+            Builtin::V4 => Family::V4,
+            Builtin::V6 => Family::V6,
+        }
+    }
+}
+
+/// 展开IP段并剔除排除的IP段
+///
+/// 与排除项部分重叠的IP段会被拆分为不包含排除项的若干子网
+///
+/// # 参数
+/// - `ranges`: 待展开的IP段(CIDR或单个IP，忽略空行)
+/// - `exclusions`: 需要剔除的IP段
+///
+/// # 返回值
+/// - 成功: 返回剔除后的IP段列表
+/// - 失败: 存在格式错误的IP段
+pub fn expand_ranges(ranges: &[String], exclusions: &[IpNet]) -> anyhow::Result<Vec<IpNet>> {
+    let mut nets = Vec::new();
+    for range in ranges.iter().filter(|range| !range.trim().is_empty()) {
+        let mut parts = vec![catalog::parse_range(range)?.trunc()];
+        for excluded in exclusions {
+            parts = parts
+                .into_iter()
+                .flat_map(|net| subtract(net, excluded))
+                .collect();
+        }
+        nets.extend(parts);
+    }
 
-    Ok(response::success())
+    Ok(nets)
+}
+
+/// 从IP段中剔除另一个IP段
+///
+/// CIDR之间只有包含或不相交两种关系，
+/// 当排除项位于IP段内部时将IP段一分为二递归处理
+fn subtract(net: IpNet, excluded: &IpNet) -> Vec<IpNet> {
+    if excluded.contains(&net) {
+        return Vec::new();
+    }
+    if !net.contains(excluded) {
+        return vec![net];
+    }
+
+    net.subnets(net.prefix_len() + 1)
+        .map(|halves| halves.flat_map(|half| subtract(half, excluded)).collect())
+        .unwrap_or_default()
+}
+
+/// 统计IP段包含的IP总数(溢出时取最大值)
+fn count_ips(nets: &[IpNet]) -> u128 {
+    nets.iter()
+        .map(|net| {
+            let bits = net.max_prefix_len() - net.prefix_len();
+            1u128.checked_shl(u32::from(bits)).unwrap_or(u128::MAX)
+        })
+        .fold(0, u128::saturating_add)
 }
 
 /// 实际执行IP选择的核心逻辑
 /// # 参数
 /// - `ip_ranges`: 要测试的IP地址列表
+/// - `timeout`: 任务最长执行时间
 /// # 返回值
-/// - 成功: Ok(())
-/// - 失败: 返回具体错误信息
-async fn _select(ip_ranges: Vec<String>) -> anyhow::Result<()> {
+/// - 成功: 返回有效结果和结果文件内容
+/// - 失败: 返回失败信息
+async fn _select(
+    ip_ranges: Vec<String>,
+    timeout: Duration,
+) -> Result<(Vec<IpResult>, String), Failure> {
     // 将IP列表写入临时文件ip.txt
-    fs::write("ip.txt", ip_ranges.join("\n")).await?;
+    fs::write("ip.txt", ip_ranges.join("\n"))
+        .await
+        .map_err(|e| Failure::new(FailureKind::Input, e))?;
 
     // 调用CloudflareSpeedTest命令行工具，该工具会测试IP延迟并生成result.csv结果文件
-    let mut cmd = Command::new("CloudflareSpeedTest");
-    let output = cmd.output().await?;
+    let output = execute(Command::new("CloudflareSpeedTest"), timeout).await?;
 
     // 检查命令执行结果
     if !output.status.success() {
         let err_msg = String::from_utf8_lossy(&output.stderr);
-        return Err(anyhow::anyhow!("execute command failed: {}", err_msg));
+        return Err(Failure {
+            exit_code: output.status.code(),
+            ..Failure::new(
+                FailureKind::Exit,
+                format!("execute command failed: {}", err_msg),
+            )
+        });
     }
 
-    Ok(())
+    let text = fs::read_to_string("result.csv")
+        .await
+        .map_err(|e| Failure::new(FailureKind::Result, e))?;
+    let results = valid_results(&text).map_err(|e| Failure::new(FailureKind::Result, e))?;
+    Ok((results, text))
 }
 
+/// 执行测速命令并等待结束
+///
+/// 超时后丢弃的子进程会被终止
+///
+/// # 参数
+/// - `cmd`: 测速命令
+/// - `timeout`: 最长执行时间
+/// # 返回值
+/// - 成功: 返回命令的输出(不检查退出码)
+/// - 失败: 超时或无法启动命令
+async fn execute(mut cmd: Command, timeout: Duration) -> Result<Output, Failure> {
+    cmd.kill_on_drop(true);
+    time::timeout(timeout, cmd.output())
+        .await
+        .map_err(|_| {
+            warn!("CloudflareSpeedTest killed after {:?}", timeout);
+            Failure::new(FailureKind::Timeout, format!("killed after {:?}", timeout))
+        })?
+        .map_err(|e| Failure::new(FailureKind::Spawn, e))
+}
+
+/// 长轮询最长等待时间(秒)
+const MAX_WAIT_SECS: u64 = 60;
+
 /// 获取当前IP选择任务的状态
+///
+/// 指定`wait`时为长轮询: 状态版本号不等于`since`(未指定时为当前版本号)时立即返回，
+/// 否则等待状态变化或超时后返回
+///
+/// # 参数
+/// - `req`: 长轮询参数(可选)
+///
 /// # 返回值
-/// - 成功: 返回当前状态
-/// - 失败: 如果状态为Failed，返回错误信息
-pub async fn status() -> anyhow::Result<Resp<Status>, Resp<()>> {
-    let status = STATUS.read()?.clone();
-    if let Status::Failed(err) = status {
-        return Err(err.into());
-    }
+/// - 成功: 返回当前状态、版本号、任务信息及排队中的任务(任务失败同样作为数据返回)
+pub async fn status(
+    State(state): State<AppState>,
+    Query(req): Query<StatusRequest>,
+) -> anyhow::Result<Resp<SelectStatus>, Resp<()>> {
+    let snapshot = match req.wait {
+        Some(wait) => {
+            let since = req.since.unwrap_or_else(|| state.jobs.snapshot().version);
+            let wait = Duration::from_secs(wait.min(MAX_WAIT_SECS));
+            state.jobs.wait_for_change(since, wait).await
+        }
+        None => state.jobs.snapshot(),
+    };
 
-    Ok(response::success_data(status))
+    Ok(response::success_data(SelectStatus {
+        version: snapshot.version,
+        status: snapshot.status(),
+        elapsed_secs: snapshot.current.as_ref().and_then(JobInfo::elapsed_secs),
+        job: snapshot.current,
+        timeout_secs: state.config.job.timeout_secs,
+        queue_depth: state.config.job.queue_depth,
+        queue: snapshot.queue.into_iter().map(|job| job.info).collect(),
+    }))
+}
+
+/// 任务状态查询请求
+///
+/// # 字段
+/// - `wait`: 长轮询最长等待时间(秒，最大60)
+/// - `since`: 调用方已知的状态版本号
+#[derive(Deserialize)]
+pub struct StatusRequest {
+    pub wait: Option<u64>,
+    pub since: Option<u64>,
 }
 
 /// 获取已选择的IP结果
+///
+/// 保持旧版本的返回格式，每个IP为结果文件中的一行原始字段，
+/// 需要过滤、排序或分页时使用`GET /ip/results`
+///
 /// # 返回值
-/// - 成功: 返回筛选后的IP列表(只包含延迟>0的IP)
+/// - 成功: 返回有效IP的结果行(只包含延迟>0的IP)
 /// - 失败: 返回错误响应
-pub async fn selected() -> anyhow::Result<Resp<Vec<Vec<String>>>, Resp<()>> {
-    // 读取结果文件并过滤有效IP，格式为CSV，包含IP地址和延迟数据
-    let result: Vec<Vec<String>> = tokio::fs::read_to_string("result.csv")
-        .await?
-        .lines()
+pub async fn selected(
+    State(state): State<AppState>,
+) -> anyhow::Result<Resp<Vec<Vec<String>>>, Resp<()>> {
+    let text = match state.job_history.latest_success().await {
+        Some(job) => state.job_history.results(job.id).await?,
+        None => String::new(),
+    };
+    Ok(response::success_data(result_rows(&text)?))
+}
+
+/// 查询最近一次优选的结果
+/// # 参数
+/// - `query`: 过滤、排序和分页参数
+/// # 返回值
+/// - 成功: 返回过滤后的总数和当前页的IP(只包含延迟>0的IP)
+/// - 失败: 返回错误响应
+pub async fn results(
+    State(state): State<AppState>,
+    Query(query): Query<ResultQuery>,
+) -> anyhow::Result<Resp<ResultPage>, Resp<()>> {
+    let result = latest_results(&state).await?;
+    Ok(response::success_data(query.apply(result)))
+}
+
+/// 优选结果查询参数
+///
+/// 所有参数均可选，未指定排序字段时保持优选顺序
+///
+/// # 字段
+/// - `offset`: 跳过的条数
+/// - `limit`: 返回的最大条数
+/// - `sort`: 排序字段
+/// - `order`: 排序方向(默认升序)
+/// - `max_latency`: 最大延迟(毫秒)
+/// - `max_loss`: 最大丢包率
+/// - `min_speed`: 最小下载速度(MB/s)
+/// - `colo`: 地区码，多个用逗号分隔(不区分大小写)
+/// - `family`: 地址族(v4、v6)
+#[derive(Deserialize, Default)]
+pub struct ResultQuery {
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+    pub sort: Option<SortField>,
+    pub order: Option<SortOrder>,
+    pub max_latency: Option<f64>,
+    pub max_loss: Option<f64>,
+    pub min_speed: Option<f64>,
+    pub colo: Option<String>,
+    pub family: Option<Family>,
+}
+
+/// 排序字段
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    /// 平均延迟
+    Latency,
+    /// 丢包率
+    Loss,
+    /// 下载速度
+    Speed,
+}
+
+/// 排序方向
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    /// 升序
+    #[default]
+    Asc,
+    /// 降序
+    Desc,
+}
+
+/// 分页后的优选结果
+///
+/// # 字段
+/// - `total`: 过滤后的总条数
+/// - `items`: 当前页的结果
+#[derive(Serialize)]
+pub struct ResultPage {
+    pub total: usize,
+    pub items: Vec<IpResult>,
+}
+
+impl ResultQuery {
+    /// 依次过滤、排序、分页
+    pub fn apply(&self, results: Vec<IpResult>) -> ResultPage {
+        let colos: Option<Vec<String>> = self.colo.as_ref().map(|colo| {
+            colo.split(',')
+                .map(|c| c.trim().to_uppercase())
+                .filter(|c| !c.is_empty())
+                .collect()
+        });
+
+        let mut items: Vec<IpResult> = results
+            .into_iter()
+            .filter(|r| self.max_latency.is_none_or(|max| r.latency_ms <= max))
+            .filter(|r| self.max_loss.is_none_or(|max| r.loss <= max))
+            .filter(|r| self.min_speed.is_none_or(|min| r.speed_mbps >= min))
+            .filter(|r| {
+                colos.as_ref().is_none_or(|colos| {
+                    r.colo
+                        .as_ref()
+                        .is_some_and(|colo| colos.contains(&colo.to_uppercase()))
+                })
+            })
+            .filter(|r| match self.family {
+                None | Some(Family::All) => true,
+                Some(Family::V4) => r.ip.parse::<IpAddr>().is_ok_and(|ip| ip.is_ipv4()),
+                Some(Family::V6) => r.ip.parse::<IpAddr>().is_ok_and(|ip| ip.is_ipv6()),
+            })
+            .collect();
+
+        if let Some(field) = self.sort {
+            let key = |r: &IpResult| match field {
+                SortField::Latency => r.latency_ms,
+                SortField::Loss => r.loss,
+                SortField::Speed => r.speed_mbps,
+            };
+            // 稳定排序，相同值保持优选顺序
+            let order = self.order.unwrap_or_default();
+            items.sort_by(|a, b| match order {
+                SortOrder::Asc => key(a).total_cmp(&key(b)),
+                SortOrder::Desc => key(b).total_cmp(&key(a)),
+            });
+        }
+
+        let total = items.len();
+        let items = items
+            .into_iter()
+            .skip(self.offset.unwrap_or(0))
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect();
+
+        ResultPage { total, items }
+    }
+}
+
+/// 获取最近一次优选的最优IP
+///
+/// 供脚本和路由器使用，`format=text`时每行一个IP，不包含响应包装
+///
+/// # 参数
+/// - `req`: 返回格式和数量
+///
+/// # 返回值
+/// - 成功: 返回前`n`个IP
+/// - 失败: `n`为0时返回InvalidParameter(纯文本格式为HTTP 400)，
+///   还没有优选结果时返回NotFound(纯文本格式为HTTP 404)
+pub async fn best(State(state): State<AppState>, Query(req): Query<BestRequest>) -> Response {
+    let format = req.format.unwrap_or_default();
+    let n = req.n.unwrap_or(1);
+    if n == 0 {
+        return format.error(Code::InvalidParameter, "n must be positive".to_string());
+    }
+    let results = match latest_results(&state).await {
+        Ok(results) => results,
+        Err(e) => return format.error(Code::InternalError, e.to_string()),
+    };
+
+    let best: Vec<IpResult> = results.into_iter().take(n).collect();
+    if best.is_empty() {
+        return format.error(Code::NotFound, "no selection result yet".to_string());
+    }
+
+    match format {
+        BestFormat::Text => {
+            let text: String = best.iter().map(|r| format!("{}\n", r.ip)).collect();
+            (
+                [(header::CONTENT_TYPE, mime::TEXT_PLAIN_UTF_8.as_ref())],
+                text,
+            )
+                .into_response()
+        }
+        BestFormat::Json => response::success_data(best).into_response(),
+    }
+}
+
+/// 最优IP查询参数
+///
+/// # 字段
+/// - `format`: 返回格式(默认json)
+/// - `n`: 返回的IP数量(默认1，必须为正数)
+#[derive(Deserialize)]
+pub struct BestRequest {
+    pub format: Option<BestFormat>,
+    pub n: Option<usize>,
+}
+
+/// 最优IP返回格式
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BestFormat {
+    /// 每行一个IP的纯文本
+    Text,
+    /// 统一JSON响应
+    #[default]
+    Json,
+}
+
+impl BestFormat {
+    /// 按格式构造错误响应
+    ///
+    /// 纯文本格式使用对应的HTTP状态码，便于`curl -f`等工具判断失败
+    fn error(self, code: Code, message: String) -> Response {
+        match self {
+            BestFormat::Text => {
+                let status = match code {
+                    Code::InvalidParameter => StatusCode::BAD_REQUEST,
+                    Code::NotFound => StatusCode::NOT_FOUND,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, format!("{}\n", message)).into_response()
+            }
+            BestFormat::Json => response::fail::<()>(code, message).into_response(),
+        }
+    }
+}
+
+/// 导出优选结果
+///
+/// 未指定任务ID时导出最近一次成功任务的结果
+///
+/// # 参数
+/// - `req`: 导出格式和任务ID
+///
+/// # 返回值
+/// - 成功: 返回对应格式的结果文件(作为附件下载)
+/// - 失败: 任务不存在或没有结果时返回NotFound
+pub async fn export(
+    State(state): State<AppState>,
+    Query(req): Query<ExportRequest>,
+) -> anyhow::Result<Response, Resp<()>> {
+    let id = match req.job {
+        Some(id) => id,
+        None => {
+            state
+                .job_history
+                .latest_success()
+                .await
+                .ok_or_else(|| {
+                    response::fail(Code::NotFound, "no selection result yet".to_string())
+                })?
+                .id
+        }
+    };
+
+    let (job, results) = load_job(&state, id).await?;
+    let format = req.format.unwrap_or_default();
+    let disposition = format!(
+        "attachment; filename=\"cfselect-job-{}.{}\"",
+        job.id,
+        format.extension()
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(stream::iter(format.lines(job.id, results))),
+    )
+        .into_response())
+}
+
+/// 导入CloudflareSpeedTest结果文件
+///
+/// 以multipart表单上传，文件字段名为`file`。
+/// 文件使用与优选结果相同的解析器校验，导入后作为已成功的任务保存，
+/// 成为最近一次成功的任务(用于优选结果查询、DNS同步和故障切换)
+///
+/// # 参数
+/// - `state`: 应用状态，包含任务状态机和任务历史
+/// - `multipart`: 上传的表单
+///
+/// # 返回值
+/// - 成功: 返回导入的任务信息
+/// - 失败: 缺少文件、文件格式错误或没有有效结果
+pub async fn import(
+    State(state): State<AppState>,
+    mut multipart: Multipart,
+) -> anyhow::Result<Resp<JobInfo>, Resp<()>> {
+    let invalid = |message: String| response::fail(Code::InvalidParameter, message);
+
+    let mut text = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| invalid(e.body_text()))?
+    {
+        if field.name() == Some("file") {
+            text = Some(field.text().await.map_err(|e| invalid(e.body_text()))?);
+            break;
+        }
+    }
+    let text = text.ok_or_else(|| invalid("missing multipart field: file".to_string()))?;
+
+    let results = valid_results(&text).map_err(|e| invalid(e.to_string()))?;
+    if results.is_empty() {
+        return Err(invalid("no valid result in file".to_string()));
+    }
+
+    let job = JobInfo::imported(state.jobs.allocate_id(), results.len());
+    state.job_history.record(job.clone(), Some(&text)).await?;
+    api::job::record_measurements(&state, &job, &results).await;
+    info!("imported job {} with {} results", job.id, results.len());
+
+    Ok(response::success_data(job))
+}
+
+/// 读取已成功任务的信息和有效结果
+///
+/// # 参数
+/// - `state`: 应用状态，包含任务历史
+/// - `id`: 任务ID
+///
+/// # 返回值
+/// - 成功: 返回任务信息和有效结果
+/// - 失败: 任务不存在或未成功时返回NotFound
+pub async fn load_job(
+    state: &AppState,
+    id: u64,
+) -> anyhow::Result<(JobInfo, Vec<IpResult>), Resp<()>> {
+    let job = state
+        .job_history
+        .get(id)
+        .await
+        .ok_or_else(|| response::fail(Code::NotFound, format!("job {} not found", id)))?;
+    if job.state != Status::Success {
+        return Err(response::fail(
+            Code::NotFound,
+            format!("job {} has no results ({:?})", job.id, job.state),
+        ));
+    }
+
+    let results = job_results(state, job.id).await?;
+    Ok((job, results))
+}
+
+/// 结果导出请求
+///
+/// # 字段
+/// - `format`: 导出格式(默认csv)
+/// - `job`: 任务ID(默认最近一次成功的任务)
+#[derive(Deserialize)]
+pub struct ExportRequest {
+    pub format: Option<ExportFormat>,
+    pub job: Option<u64>,
+}
+
+/// Prometheus指标: 名称、说明、取值函数
+type Metric = (&'static str, &'static str, fn(&IpResult) -> f64);
+
+/// 导出为Prometheus文本时输出的指标
+const PROMETHEUS_METRICS: [Metric; 3] = [
+    (
+        "cfselect_latency_milliseconds",
+        "Average latency of the selected IP in milliseconds.",
+        |r| r.latency_ms,
+    ),
+    (
+        "cfselect_loss_ratio",
+        "Packet loss ratio of the selected IP.",
+        |r| r.loss,
+    ),
+    (
+        "cfselect_download_speed_megabytes",
+        "Download speed of the selected IP in MB/s.",
+        |r| r.speed_mbps,
+    ),
+];
+
+/// 逐行输出的导出内容
+type Lines = Box<dyn Iterator<Item = anyhow::Result<String>> + Send>;
+
+/// 结果导出格式
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// 带标题行的CSV，可重新被结果解析器读取
+    #[default]
+    Csv,
+    /// 每行一个JSON对象
+    Ndjson,
+    /// Prometheus文本格式
+    Prometheus,
+}
+
+impl ExportFormat {
+    /// 响应的Content-Type
+    fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Ndjson => "application/x-ndjson",
+            ExportFormat::Prometheus => "text/plain; version=0.0.4; charset=utf-8",
+        }
+    }
+
+    /// 下载文件的扩展名
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Prometheus => "prom",
+        }
+    }
+
+    /// 按格式逐行输出结果
+    ///
+    /// 每次只生成一行，导出时边生成边发送，不在内存中拼接完整的文件
+    ///
+    /// # 参数
+    /// - `job`: 结果所属的任务ID
+    /// - `results`: 要输出的结果
+    ///
+    /// # 返回值
+    /// 依次返回每一行(包含换行符)，序列化失败时返回错误
+    fn lines(self, job: u64, results: Vec<IpResult>) -> Lines {
+        match self {
+            ExportFormat::Csv => Box::new(
+                iter::once(Ok(
+                    "ip,sent,received,loss,latency_ms,speed_mbps,colo\n".to_string()
+                ))
+                .chain(results.into_iter().map(|r| {
+                    Ok(format!(
+                        "{},{},{},{},{},{},{}\n",
+                        r.ip,
+                        r.sent,
+                        r.received,
+                        r.loss,
+                        r.latency_ms,
+                        r.speed_mbps,
+                        r.colo.unwrap_or_default()
+                    ))
+                })),
+            ),
+            ExportFormat::Ndjson => Box::new(results.into_iter().map(|r| {
+                let mut line = serde_json::to_string(&r)?;
+                line.push('\n');
+                Ok(line)
+            })),
+            ExportFormat::Prometheus => {
+                let results = Arc::new(results);
+                Box::new(
+                    PROMETHEUS_METRICS
+                        .into_iter()
+                        .flat_map(move |(name, help, value)| {
+                            let results = results.clone();
+                            let header =
+                                format!("# HELP {} {}\n# TYPE {} gauge\n", name, help, name);
+                            iter::once(Ok(header)).chain((0..results.len()).map(move |i| {
+                                let r = &results[i];
+                                Ok(format!(
+                                    "{}{{job=\"{}\",ip=\"{}\",colo=\"{}\"}} {}\n",
+                                    name,
+                                    job,
+                                    escape_label(&r.ip),
+                                    escape_label(r.colo.as_deref().unwrap_or_default()),
+                                    value(r)
+                                ))
+                            }))
+                        }),
+                )
+            }
+        }
+    }
+}
+
+/// 按Prometheus文本格式转义标签值
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// 读取最近一次成功任务的有效结果
+///
+/// # 返回值
+/// - 成功: 返回按优选顺序排列的有效IP(延迟>0ms)，还没有成功的任务时为空
+/// - 失败: 结果文件无法读取或格式错误
+pub async fn latest_results(state: &AppState) -> anyhow::Result<Vec<IpResult>> {
+    match state.job_history.latest_success().await {
+        Some(job) => job_results(state, job.id).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 读取指定任务的有效结果
+///
+/// # 参数
+/// - `state`: 应用状态，包含任务历史
+/// - `id`: 任务ID
+///
+/// # 返回值
+/// - 成功: 返回按优选顺序排列的有效IP(延迟>0ms)
+/// - 失败: 结果文件无法读取或格式错误
+pub async fn job_results(state: &AppState, id: u64) -> anyhow::Result<Vec<IpResult>> {
+    let text = state.job_history.results(id).await?;
+    valid_results(&text)
+}
+
+/// 解析结果文件并过滤出有效IP(延迟>0ms)
+fn valid_results(text: &str) -> anyhow::Result<Vec<IpResult>> {
+    let result = parse_results(text)?
+        .into_iter()
+        .filter(|row| row.latency_ms > 0.0)
+        .collect();
+
+    Ok(result)
+}
+
+/// 将结果文件拆分为原始字段，只保留有效IP(延迟>0ms)
+///
+/// # 参数
+/// - `text`: 结果文件内容
+///
+/// # 返回值
+/// - 成功: 返回有效IP的结果行
+/// - 失败: 存在格式错误的行
+fn result_rows(text: &str) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    for (index, line) in text.lines().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let result = parse_result(line)
+            .map_err(|e| anyhow::anyhow!("invalid result at line {}: {}", index + 1, e))?;
+        if result.latency_ms > 0.0 {
+            rows.push(line.split(',').map(|s| s.trim().to_string()).collect());
+        }
+    }
+
+    Ok(rows)
+}
+
+/// 解析CloudflareSpeedTest结果文件
+///
+/// 文件为CSV格式，首行为标题，列依次为:
+/// IP地址、已发送、已接收、丢包率、平均延迟、下载速度、地区码(可选)
+///
+/// # 参数
+/// - `text`: 结果文件内容
+///
+/// # 返回值
+/// - 成功: 返回全部结果行
+/// - 失败: 存在格式错误的行
+pub fn parse_results(text: &str) -> anyhow::Result<Vec<IpResult>> {
+    text.lines()
+        .enumerate()
         .skip(1) // 跳过CSV标题行
-        .map(|line| line.split(',').map(|s| s.to_string()).collect())
-        // 过滤出有效IP(延迟>0ms)
-        .filter(|row: &Vec<String>| {
-            row.last()
-                .is_some_and(|n| n.parse::<f64>().is_ok_and(|n| n > 0.0))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            parse_result(line)
+                .map_err(|e| anyhow::anyhow!("invalid result at line {}: {}", index + 1, e))
         })
-        .collect();
+        .collect()
+}
+
+/// 解析结果文件中的一行
+fn parse_result(line: &str) -> anyhow::Result<IpResult> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    if fields.len() < 6 {
+        return Err(anyhow::anyhow!(
+            "expected at least 6 columns, got {}",
+            fields.len()
+        ));
+    }
+
+    let ip: IpAddr = fields[0].parse()?;
+    Ok(IpResult {
+        ip: ip.to_string(),
+        sent: fields[1].parse()?,
+        received: fields[2].parse()?,
+        loss: fields[3].parse()?,
+        latency_ms: fields[4].parse()?,
+        speed_mbps: fields[5].parse()?,
+        // 地区码只保留字母和数字，其余(如N/A)视为未知
+        colo: fields
+            .get(6)
+            .filter(|colo| !colo.is_empty() && colo.chars().all(|c| c.is_ascii_alphanumeric()))
+            .map(|colo| colo.to_string()),
+    })
+}
+
+/// 获取内置Cloudflare IP段目录
+/// # 返回值
+/// - 成功: 返回当前使用的IP段目录
+pub async fn ranges(State(state): State<AppState>) -> anyhow::Result<Resp<Catalog>, Resp<()>> {
+    let catalog = state.catalog.read().await.clone();
+    Ok(response::success_data(catalog))
+}
+
+/// 刷新内置Cloudflare IP段目录
+///
+/// # 参数
+/// - `state`: 应用状态，包含刷新来源配置
+/// - `req`: 刷新来源，未指定时优先使用已配置的本地文件
+///
+/// # 返回值
+/// - 成功: 返回刷新后的IP段目录
+/// - 失败: 来源未配置、获取失败或内容无法解析
+pub async fn refresh_ranges(
+    State(state): State<AppState>,
+    Query(req): Query<RefreshRequest>,
+) -> anyhow::Result<Resp<Catalog>, Resp<()>> {
+    let conf = &state.config.catalog;
+    let from = req.from.unwrap_or(if conf.file.is_empty() {
+        RefreshSource::Url
+    } else {
+        RefreshSource::File
+    });
+
+    let catalog = match from {
+        RefreshSource::Url => {
+            if conf.urls.is_empty() {
+                return Err(response::fail(
+                    Code::InvalidParameter,
+                    "catalog urls not configured".to_string(),
+                ));
+            }
+            let text = cloudflare::fetch_ranges(&conf.urls).await?;
+            Catalog::parse(&text, &conf.urls.join(","))?
+        }
+        RefreshSource::File => {
+            if conf.file.is_empty() {
+                return Err(response::fail(
+                    Code::InvalidParameter,
+                    "catalog file not configured".to_string(),
+                ));
+            }
+            let text = fs::read_to_string(&conf.file).await?;
+            Catalog::parse(&text, &conf.file)?
+        }
+    };
+
+    info!(
+        "ip ranges refreshed from {}: {} v4, {} v6",
+        catalog.source,
+        catalog.v4.len(),
+        catalog.v6.len()
+    );
+    state
+        .catalog
+        .update(|current| *current = catalog.clone())
+        .await?;
+
+    Ok(response::success_data(catalog))
+}
+
+/// IP段目录刷新请求
+///
+/// # 字段
+/// - `from`: 刷新来源(可选)
+#[derive(Deserialize)]
+pub struct RefreshRequest {
+    pub from: Option<RefreshSource>,
+}
+
+/// IP段目录刷新来源
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum RefreshSource {
+    /// 从配置的URL在线获取
+    Url,
+    /// 从配置的本地文件读取
+    File,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configure::AppConfig;
+    use axum::extract::{FromRequest, Request};
+
+    const RESULTS: &str = "IP 地址,已发送,已接收,丢包率,平均延迟,下载速度 (MB/s),地区码\n\
+                           104.16.1.1,4,4,0.00,120.50,12.30,SJC\n\
+                           104.16.1.2,4,0,1.00,0.00,0.00,SJC\n";
+
+    /// 以multipart表单上传结果文件，返回导入的任务或响应码
+    async fn import_file(state: &AppState, text: &str) -> Result<JobInfo, Code> {
+        let body = format!(
+            "--b\r\ncontent-disposition: form-data; name=\"file\"; filename=\"result.csv\"\r\n\r\n{}\r\n--b--\r\n",
+            text
+        );
+        let req = Request::builder()
+            .header(header::CONTENT_TYPE, "multipart/form-data; boundary=b")
+            .body(Body::from(body))
+            .unwrap();
+        let multipart = Multipart::from_request(req, &()).await.unwrap();
+        match import(State(state.clone()), multipart).await {
+            Ok(resp) => Ok(resp.data.unwrap()),
+            Err(resp) => Err(resp.code),
+        }
+    }
+
+    /// 测试导入的任务与优选任务共用递增的任务ID，超出保留数量时删除最早的任务
+    #[tokio::test]
+    async fn test_import() {
+        let state = AppState::new(AppConfig::test("[job]\nhistory_size = 2"))
+            .await
+            .unwrap();
 
-    Ok(response::success_data(result))
+        let job = import_file(&state, RESULTS).await.unwrap();
+        assert_eq!(
+            (job.id, job.state, job.result_count),
+            (1, Status::Success, Some(1))
+        );
+        assert!(matches!(
+            state.jobs.submit(vec!["1.1.1.0/24".to_string()], 256),
+            Submit::Started(started) if started.id == 2
+        ));
+        for id in [3, 4] {
+            assert_eq!(import_file(&state, RESULTS).await.unwrap().id, id);
+        }
+
+        assert!(state.job_history.get(1).await.is_none());
+        assert!(state.job_history.results(1).await.is_err());
+        assert_eq!(state.job_history.latest_success().await.unwrap().id, 4);
+        let rows = result_rows(&state.job_history.results(4).await.unwrap()).unwrap();
+        assert_eq!(rows.len(), 1);
+
+        // 重启后继续分配任务ID
+        let state = AppState::new((*state.config).clone()).await.unwrap();
+        assert_eq!(import_file(&state, RESULTS).await.unwrap().id, 5);
+    }
+
+    /// 测试导入格式错误或没有有效结果的文件被拒绝，不分配任务ID
+    #[tokio::test]
+    async fn test_import_invalid() {
+        let state = AppState::new(AppConfig::test("")).await.unwrap();
+
+        let malformed = "header\n104.16.1.1,4,x,0.00,120.50,12.30\n";
+        let no_valid = "header\n104.16.1.2,4,0,1.00,0.00,0.00\n";
+        for text in [malformed, no_valid, ""] {
+            assert_eq!(
+                import_file(&state, text).await.unwrap_err(),
+                Code::InvalidParameter
+            );
+        }
+        assert!(state.job_history.latest_success().await.is_none());
+        assert_eq!(import_file(&state, RESULTS).await.unwrap().id, 1);
+    }
+
+    /// 测试排除单个IP时IP段被拆分
+    #[test]
+    fn test_expand_ranges_with_exclusion() {
+        let ranges = vec!["1.1.1.0/30".to_string(), "2.2.2.0/24".to_string()];
+        let exclusions = vec!["1.1.1.1/32".parse().unwrap(), "2.2.0.0/16".parse().unwrap()];
+        let nets: Vec<String> = expand_ranges(&ranges, &exclusions)
+            .unwrap()
+            .iter()
+            .map(IpNet::to_string)
+            .collect();
+        assert_eq!(nets, vec!["1.1.1.0/32", "1.1.1.2/31"]);
+    }
+
+    /// 测试旧格式结果保留原始字段并跳过无效IP
+    #[test]
+    fn test_result_rows() {
+        let text = "IP 地址,已发送,已接收,丢包率,平均延迟,下载速度 (MB/s),地区码\n\
+                    104.16.1.1,4,4,0.00,120.50,12.30,SJC\n\
+                    104.16.1.2,4,0,1.00,0.00,0.00\n\
+                    \n";
+        let rows = result_rows(text).unwrap();
+        assert_eq!(
+            rows,
+            vec![vec![
+                "104.16.1.1",
+                "4",
+                "4",
+                "0.00",
+                "120.50",
+                "12.30",
+                "SJC"
+            ]]
+        );
+        assert!(result_rows("").unwrap().is_empty());
+        assert!(result_rows("header\n1.1.1.1,4,x,0,0,0").is_err());
+    }
+
+    /// 测试测速命令超时后被终止，任务状态为超时
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_execute_timeout() {
+        let pid_file =
+            std::env::temp_dir().join(format!("cfselect-test-{}.pid", std::process::id()));
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(format!("echo $$ > {}; exec sleep 30", pid_file.display()));
+
+        let started = std::time::Instant::now();
+        let failure = execute(cmd, Duration::from_millis(500)).await.unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(failure.kind, FailureKind::Timeout);
+
+        let mut job = JobInfo::new(1, 1, 1);
+        job.finish(Err(failure));
+        assert_eq!(job.state, Status::TimedOut);
+
+        // 子进程在后台回收，等待其退出(不存在或已成为僵尸进程)
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        std::fs::remove_file(&pid_file).unwrap();
+        let stat = format!("/proc/{}/stat", pid.trim());
+        let alive = || std::fs::read_to_string(&stat).is_ok_and(|stat| !stat.contains(") Z "));
+        for _ in 0..50 {
+            if !alive() {
+                break;
+            }
+            time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(!alive());
+    }
+
+    /// 测试解析CloudflareSpeedTest结果文件
+    #[test]
+    fn test_parse_results() {
+        let text = "IP 地址,已发送,已接收,丢包率,平均延迟,下载速度 (MB/s),地区码\n\
+                    104.16.1.1,4,4,0.00,120.50,12.30,SJC\n\
+                    104.16.1.2,4,3,0.25,130.00,0.00\n";
+        let results = parse_results(text).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].colo.as_deref(), Some("SJC"));
+        assert_eq!(results[1].loss, 0.25);
+        assert!(parse_results("header\n1.1.1.1,4,x,0,0,0").is_err());
+
+        let results =
+            parse_results("header\n1.1.1.1,4,4,0,1,1,N/A\n1.1.1.2,4,4,0,1,1,S\"} 1").unwrap();
+        assert_eq!(results[0].colo, None);
+        assert_eq!(results[1].colo, None);
+    }
+
+    /// 测试优选结果的过滤、排序和分页
+    #[test]
+    fn test_result_query() {
+        let text = "header\n\
+                    104.16.1.1,4,4,0.00,180.00,10.00,SJC\n\
+                    104.16.1.2,4,4,0.00,120.00,5.00,LAX\n\
+                    104.16.1.3,4,3,0.25,100.00,8.00,sjc\n\
+                    2606:4700::1,4,4,0.00,90.00,9.00,HKG\n";
+        let results = parse_results(text).unwrap();
+
+        let query = ResultQuery {
+            max_latency: Some(150.0),
+            family: Some(Family::V4),
+            sort: Some(SortField::Latency),
+            ..Default::default()
+        };
+        let page = query.apply(results.clone());
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items[0].ip, "104.16.1.3");
+
+        let query = ResultQuery {
+            colo: Some("sjc".to_string()),
+            sort: Some(SortField::Speed),
+            order: Some(SortOrder::Desc),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let page = query.apply(results);
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].ip, "104.16.1.1");
+    }
+
+    /// 测试导出格式，CSV导出结果可被重新解析
+    #[test]
+    fn test_export_render() {
+        let text = "header\n\
+                    104.16.1.1,4,4,0.00,120.50,12.30,SJC\n\
+                    104.16.1.2,4,3,0.25,130.00,0.00\n";
+        let results = parse_results(text).unwrap();
+        let render = |format: ExportFormat| {
+            format
+                .lines(3, results.clone())
+                .collect::<anyhow::Result<String>>()
+                .unwrap()
+        };
+
+        let csv = render(ExportFormat::Csv);
+        let parsed = parse_results(&csv).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].colo.as_deref(), Some("SJC"));
+        assert_eq!(parsed[1].colo, None);
+
+        let ndjson = render(ExportFormat::Ndjson);
+        assert_eq!(ndjson.lines().count(), 2);
+
+        let prom = render(ExportFormat::Prometheus);
+        assert!(prom.contains(
+            "cfselect_latency_milliseconds{job=\"3\",ip=\"104.16.1.1\",colo=\"SJC\"} 120.5\n"
+        ));
+        assert!(prom.contains("# TYPE cfselect_loss_ratio gauge\n"));
+        assert_eq!(escape_label("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+    }
+
+    /// 测试格式错误的IP段
+    #[test]
+    fn test_expand_ranges_invalid() {
+        assert!(expand_ranges(&["1.1.1.0/33".to_string()], &[]).is_err());
+    }
 }
@@ -0,0 +1,472 @@
+//! 优选任务历史API模块
+//!
+//! 基于已保存的优选任务结果和测量历史提供跨任务分析：
+//! - 对比两次优选任务的前N个IP(/jobs/{a}/diff/{b})
+//! - 查询单个IP的历史时间序列，数据点过多时降采样(/{ip}/history)
+//! - 综合最近多次任务的稳定性排名(/ranking)
+
+use crate::api::ip::{job_results, load_job};
+use crate::model::history::{
+    Delta, HistoryPoint, IpChange, IpHistory, JobDiff, Measurement, RankedIp, Ranking, WinnerChange,
+};
+use crate::model::response;
+use crate::model::response::{Code, Resp};
+use crate::model::select::{IpResult, JobInfo};
+use crate::server::state::AppState;
+use axum::extract::{Path, Query, State};
+use chrono::{DateTime, Duration, Local};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use tracing::error;
+
+/// 默认对比的IP数量
+const DEFAULT_TOP: usize = 10;
+
+/// 对比两次优选任务
+///
+/// # 参数
+/// - `ids`: 基准任务ID和对比任务ID
+/// - `req`: 对比的IP数量
+///
+/// # 返回值
+/// - 成功: 返回前N个IP的进出、指标变化及最优IP是否变化
+/// - 失败: 任务不存在或未成功时返回NotFound
+pub async fn diff(
+    State(state): State<AppState>,
+    Path((from, to)): Path<(u64, u64)>,
+    Query(req): Query<DiffRequest>,
+) -> anyhow::Result<Resp<JobDiff>, Resp<()>> {
+    let (_, before) = load_job(&state, from).await?;
+    let (_, after) = load_job(&state, to).await?;
+    let top = req.n.unwrap_or(DEFAULT_TOP);
+
+    let mut diff = compare(&before, &after, top);
+    diff.from = from;
+    diff.to = to;
+
+    Ok(response::success_data(diff))
+}
+
+/// 任务对比请求
+///
+/// # 字段
+/// - `n`: 对比的前N个IP(默认10)
+#[derive(Deserialize)]
+pub struct DiffRequest {
+    pub n: Option<usize>,
+}
+
+/// 对比两次任务的结果
+///
+/// 结果均按优选顺序排列，只比较各自的前`top`个IP；
+/// 进出前N的IP如果在另一次任务中存在(排名在N之后)，同样给出指标变化
+///
+/// # 参数
+/// - `before`: 基准任务的结果
+/// - `after`: 对比任务的结果
+/// - `top`: 对比的IP数量
+///
+/// # 返回值
+/// 返回对比结果(任务ID为0，由调用方填充)
+fn compare(before: &[IpResult], after: &[IpResult], top: usize) -> JobDiff {
+    let top_before = &before[..top.min(before.len())];
+    let top_after = &after[..top.min(after.len())];
+    let contains = |results: &[IpResult], ip: &str| results.iter().any(|r| r.ip == ip);
+
+    let entered = top_after
+        .iter()
+        .filter(|r| !contains(top_before, &r.ip))
+        .cloned()
+        .collect();
+    let left = top_before
+        .iter()
+        .filter(|r| !contains(top_after, &r.ip))
+        .cloned()
+        .collect();
+
+    // 按对比任务中的顺序列出前N范围内、两次任务都存在的IP
+    let mut ips: Vec<&str> = top_after.iter().map(|r| r.ip.as_str()).collect();
+    ips.extend(
+        top_before
+            .iter()
+            .map(|r| r.ip.as_str())
+            .filter(|ip| !contains(top_after, ip)),
+    );
+    let changed = ips
+        .into_iter()
+        .filter_map(|ip| {
+            let (rank_from, a) = position_of(before, ip)?;
+            let (rank_to, b) = position_of(after, ip)?;
+            Some(IpChange {
+                ip: ip.to_string(),
+                rank_from,
+                rank_to,
+                latency_ms: Delta::new(a.latency_ms, b.latency_ms),
+                speed_mbps: Delta::new(a.speed_mbps, b.speed_mbps),
+            })
+        })
+        .collect();
+
+    let winner_from = before.first().map(|r| r.ip.clone());
+    let winner_to = after.first().map(|r| r.ip.clone());
+    JobDiff {
+        from: 0,
+        to: 0,
+        top,
+        winner: WinnerChange {
+            changed: winner_from != winner_to,
+            from: winner_from,
+            to: winner_to,
+        },
+        entered,
+        left,
+        changed,
+    }
+}
+
+/// 查找IP的排名(从1开始)和结果
+fn position_of<'a>(results: &'a [IpResult], ip: &str) -> Option<(usize, &'a IpResult)> {
+    results
+        .iter()
+        .position(|r| r.ip == ip)
+        .map(|index| (index + 1, &results[index]))
+}
+
+/// 获取单个IP的历史时间序列
+///
+/// 未指定`bucket_secs`且数据点超过`points`(默认取配置)时，
+/// 自动按时间段聚合，使返回的点数不超过上限
+///
+/// # 参数
+/// - `ip`: IP地址
+/// - `req`: 时间范围和降采样参数
+///
+/// # 返回值
+/// - 成功: 返回按时间先后排列的延迟、丢包率和下载速度
+/// - 失败: IP或参数格式错误
+pub async fn history(
+    State(state): State<AppState>,
+    Path(ip): Path<String>,
+    Query(req): Query<HistoryRequest>,
+) -> anyhow::Result<Resp<IpHistory>, Resp<()>> {
+    let ip: IpAddr = ip
+        .parse()
+        .map_err(|_| response::fail(Code::InvalidParameter, format!("invalid ip: {}", ip)))?;
+    if req.bucket_secs.is_some_and(|secs| secs <= 0) {
+        return Err(response::fail(
+            Code::InvalidParameter,
+            "bucket_secs must be positive".to_string(),
+        ));
+    }
+
+    let ip = ip.to_string();
+    let mut measurements = state.measurements.query(&ip, req.from, req.to).await?;
+    measurements.sort_by_key(|m| m.measured_at);
+
+    let total = measurements.len();
+    let max_points = req.points.unwrap_or(state.config.history.max_points).max(1);
+    let bucket_secs = req
+        .bucket_secs
+        .or_else(|| (total > max_points).then(|| auto_bucket(&measurements, max_points)));
+    let points = match bucket_secs {
+        Some(secs) => downsample(measurements, secs),
+        None => measurements.into_iter().map(HistoryPoint::from).collect(),
+    };
+
+    Ok(response::success_data(IpHistory {
+        ip,
+        total,
+        bucket_secs,
+        points,
+    }))
+}
+
+/// IP历史查询参数
+///
+/// # 字段
+/// - `from`: 起始时间(RFC 3339)
+/// - `to`: 结束时间(RFC 3339)
+/// - `bucket_secs`: 聚合粒度(秒)
+/// - `points`: 自动降采样时的最大点数
+#[derive(Deserialize)]
+pub struct HistoryRequest {
+    pub from: Option<DateTime<Local>>,
+    pub to: Option<DateTime<Local>>,
+    pub bucket_secs: Option<i64>,
+    pub points: Option<usize>,
+}
+
+/// 将已结束任务的结果写入测量历史
+///
+/// # 参数
+/// - `state`: 应用状态
+/// - `job`: 已结束的任务
+/// - `results`: 任务的有效结果
+pub async fn record_measurements(state: &AppState, job: &JobInfo, results: &[IpResult]) {
+    let measured_at = job.finished_at.unwrap_or_else(Local::now);
+    let measurements: Vec<Measurement> = results
+        .iter()
+        .map(|result| Measurement::from_result(job.id, measured_at, result))
+        .collect();
+    if let Err(e) = state.measurements.append(&measurements).await {
+        error!("Failed to record measurements of job {}: {}", job.id, e);
+    }
+}
+
+/// 计算使点数不超过上限的聚合粒度(秒)
+fn auto_bucket(measurements: &[Measurement], max_points: usize) -> i64 {
+    let span = match (measurements.first(), measurements.last()) {
+        (Some(first), Some(last)) => (last.measured_at - first.measured_at).num_seconds(),
+        _ => 0,
+    };
+
+    span / max_points as i64 + 1
+}
+
+/// 按时间段聚合测量记录
+///
+/// 时间段从第一条记录开始划分，每个时间段取各指标的平均值，
+/// 没有延迟或速度数据的记录不参与对应指标的平均
+///
+/// # 参数
+/// - `measurements`: 按时间先后排列的测量记录
+/// - `bucket_secs`: 聚合粒度(秒)
+///
+/// # 返回值
+/// 返回每个非空时间段的数据点
+fn downsample(measurements: Vec<Measurement>, bucket_secs: i64) -> Vec<HistoryPoint> {
+    let Some(origin) = measurements.first().map(|m| m.measured_at) else {
+        return Vec::new();
+    };
+
+    let mut buckets: Vec<(i64, Vec<Measurement>)> = Vec::new();
+    for m in measurements {
+        let index = (m.measured_at - origin).num_seconds() / bucket_secs;
+        match buckets.last_mut() {
+            Some((last, group)) if *last == index => group.push(m),
+            _ => buckets.push((index, vec![m])),
+        }
+    }
+
+    let mean = |values: Vec<f64>| {
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+    buckets
+        .into_iter()
+        .map(|(index, group)| HistoryPoint {
+            at: origin + Duration::seconds(index * bucket_secs),
+            samples: group.len(),
+            job: None,
+            loss: mean(group.iter().map(|m| m.loss).collect()).unwrap_or_default(),
+            latency_ms: mean(group.iter().filter_map(|m| m.latency_ms).collect()),
+            speed_mbps: mean(group.iter().filter_map(|m| m.speed_mbps).collect()),
+        })
+        .collect()
+}
+
+/// 获取最近多次成功任务的稳定性排名
+///
+/// # 参数
+/// - `req`: 统计的任务数量和返回数量
+///
+/// # 返回值
+/// - 成功: 返回按得分排列的IP
+/// - 失败: 读取任务结果失败
+pub async fn ranking(
+    State(state): State<AppState>,
+    Query(req): Query<RankingRequest>,
+) -> anyhow::Result<Resp<Ranking>, Resp<()>> {
+    let jobs = req.jobs.unwrap_or(state.config.history.ranking_jobs);
+    let mut ranking = build_ranking(&state, jobs).await?;
+    ranking.items.truncate(req.limit.unwrap_or(usize::MAX));
+
+    Ok(response::success_data(ranking))
+}
+
+/// 稳定性排名请求
+///
+/// # 字段
+/// - `jobs`: 统计的最近成功任务数量(默认取配置)
+/// - `limit`: 返回的最大条数
+#[derive(Deserialize)]
+pub struct RankingRequest {
+    pub jobs: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// 统计最近多次成功任务的稳定性排名
+///
+/// # 参数
+/// - `state`: 应用状态，包含任务历史
+/// - `jobs`: 统计的最近成功任务数量
+///
+/// # 返回值
+/// - 成功: 返回排名(还没有成功任务时为空)
+/// - 失败: 读取任务结果失败
+pub async fn build_ranking(state: &AppState, jobs: usize) -> anyhow::Result<Ranking> {
+    let recent = state.job_history.recent_success(jobs).await;
+    let mut runs = Vec::with_capacity(recent.len());
+    for job in &recent {
+        runs.push(job_results(state, job.id).await?);
+    }
+
+    let items = score(&runs);
+    Ok(Ranking {
+        jobs: recent.iter().map(|job| job.id).collect(),
+        total: items.len(),
+        items,
+    })
+}
+
+/// 计算各IP在多次任务中的得分
+///
+/// 某次任务中没有该IP的有效结果即视为不可达，
+/// 得分为`(延迟中位数 + 延迟标准差) / 可达比例`，
+/// 偶尔一次延迟很低但不稳定或经常不可达的IP得分会变高
+///
+/// # 参数
+/// - `runs`: 各次任务的有效结果(从新到旧)
+///
+/// # 返回值
+/// 返回按得分升序排列的IP
+fn score(runs: &[Vec<IpResult>]) -> Vec<RankedIp> {
+    let mut by_ip: HashMap<&str, Vec<&IpResult>> = HashMap::new();
+    for result in runs.iter().flatten() {
+        by_ip.entry(result.ip.as_str()).or_default().push(result);
+    }
+
+    let mut items: Vec<RankedIp> = by_ip
+        .into_iter()
+        .map(|(ip, results)| {
+            let latencies: Vec<f64> = results.iter().map(|r| r.latency_ms).collect();
+            let mean = latencies.iter().sum::<f64>() / latencies.len() as f64;
+            let variance =
+                latencies.iter().map(|l| (l - mean).powi(2)).sum::<f64>() / latencies.len() as f64;
+            let median_latency_ms = median(latencies);
+            let latency_stddev_ms = variance.sqrt();
+            let reachability = results.len() as f64 / runs.len() as f64;
+
+            RankedIp {
+                ip: ip.to_string(),
+                score: (median_latency_ms + latency_stddev_ms) / reachability,
+                reachable_runs: results.len(),
+                reachability,
+                median_latency_ms,
+                latency_stddev_ms,
+                median_speed_mbps: median(results.iter().map(|r| r.speed_mbps).collect()),
+                colo: results[0].colo.clone(),
+            }
+        })
+        .collect();
+
+    items.sort_by(|a, b| a.score.total_cmp(&b.score).then_with(|| a.ip.cmp(&b.ip)));
+    items
+}
+
+/// 计算中位数(数据不能为空)
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ip::parse_results;
+
+    /// 测试两次任务的前N个IP对比
+    #[test]
+    fn test_compare() {
+        let before = parse_results(
+            "header\n\
+             1.1.1.1,4,4,0,100,5,SJC\n\
+             1.1.1.2,4,4,0,110,4,SJC\n\
+             1.1.1.3,4,4,0,120,3,LAX\n",
+        )
+        .unwrap();
+        let after = parse_results(
+            "header\n\
+             1.1.1.3,4,4,0,90,6,LAX\n\
+             1.1.1.1,4,4,0,105,5,SJC\n\
+             1.1.1.4,4,4,0,130,2,HKG\n",
+        )
+        .unwrap();
+
+        let diff = compare(&before, &after, 2);
+        assert!(diff.winner.changed);
+        assert_eq!(diff.winner.to.as_deref(), Some("1.1.1.3"));
+        let ips = |results: &[IpResult]| results.iter().map(|r| r.ip.clone()).collect::<Vec<_>>();
+        assert_eq!(ips(&diff.entered), vec!["1.1.1.3"]);
+        assert_eq!(ips(&diff.left), vec!["1.1.1.2"]);
+
+        // 1.1.1.2 不在对比任务中，没有指标变化
+        let changed: Vec<&str> = diff.changed.iter().map(|c| c.ip.as_str()).collect();
+        assert_eq!(changed, vec!["1.1.1.3", "1.1.1.1"]);
+        assert_eq!(diff.changed[0].rank_from, 3);
+        assert_eq!(diff.changed[0].latency_ms.change, -30.0);
+        assert_eq!(diff.changed[1].speed_mbps.change, 0.0);
+
+        let diff = compare(&before, &before, 10);
+        assert!(!diff.winner.changed);
+        assert!(diff.entered.is_empty() && diff.left.is_empty());
+    }
+
+    /// 测试稳定的IP排在偶尔最快的IP前面
+    #[test]
+    fn test_score() {
+        let runs: Vec<Vec<IpResult>> = [
+            "h\n1.1.1.1,4,4,0,50,5,SJC\n1.1.1.2,4,4,0,100,4,LAX\n",
+            "h\n1.1.1.2,4,4,0,110,4,LAX\n1.1.1.3,4,4,0,90,4,LAX\n",
+            "h\n1.1.1.2,4,4,0,90,4,LAX\n1.1.1.3,4,4,0,200,4,LAX\n",
+        ]
+        .iter()
+        .map(|text| parse_results(text).unwrap())
+        .collect();
+
+        let items = score(&runs);
+        let ips: Vec<&str> = items.iter().map(|item| item.ip.as_str()).collect();
+        assert_eq!(ips, vec!["1.1.1.2", "1.1.1.1", "1.1.1.3"]);
+        assert_eq!(items[0].reachable_runs, 3);
+        assert_eq!(items[0].median_latency_ms, 100.0);
+        assert_eq!(items[2].median_latency_ms, 145.0);
+        assert!((items[1].reachability - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(items[1].score, 150.0);
+    }
+
+    /// 测试按时间段降采样
+    #[test]
+    fn test_downsample() {
+        let origin = Local::now();
+        let measurement =
+            |secs: i64, latency_ms: Option<f64>, speed_mbps: Option<f64>| Measurement {
+                ip: "1.1.1.1".to_string(),
+                measured_at: origin + Duration::seconds(secs),
+                job: None,
+                loss: if latency_ms.is_some() { 0.0 } else { 1.0 },
+                latency_ms,
+                speed_mbps,
+            };
+        let measurements = vec![
+            measurement(0, Some(100.0), Some(4.0)),
+            measurement(30, Some(120.0), None),
+            measurement(59, None, None),
+            measurement(200, Some(80.0), Some(6.0)),
+        ];
+        assert_eq!(auto_bucket(&measurements, 2), 101);
+
+        let points = downsample(measurements, 60);
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].samples, 3);
+        assert_eq!(points[0].latency_ms, Some(110.0));
+        assert_eq!(points[0].speed_mbps, Some(4.0));
+        assert!((points[0].loss - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(points[1].at, origin + Duration::seconds(180));
+        assert_eq!(points[1].samples, 1);
+    }
+}
@@ -2,9 +2,15 @@
 //!
 //! 包含应用的所有API端点处理逻辑：
 //! - `dns`: DNS记录同步相关API
+//! - `exclusion`: IP排除列表相关API
 //! - `ip`: IP优选相关API
+//! - `job`: 优选任务历史分析API
+//! - `preset`: IP段预设相关API
 //! - `server`: 服务器健康检查API
 
 pub mod dns;
+pub mod exclusion;
 pub mod ip;
+pub mod job;
+pub mod preset;
 pub mod server;
@@ -0,0 +1,354 @@
+//! IP段预设API模块
+//!
+//! 在服务端保存命名的IP段集合，供所有客户端共享：
+//! - 查询预设列表与单个预设
+//! - 创建、修改、删除预设
+//!
+//! 优选任务可以通过预设名称引用其中的IP段，重复的IP段只保留一个
+
+use crate::catalog;
+use crate::model::preset::Preset;
+use crate::model::response;
+use crate::model::response::{Code, Resp};
+use crate::server::state::AppState;
+use axum::Json;
+use axum::extract::{Path, State};
+use chrono::Local;
+use serde::Deserialize;
+use std::collections::HashSet;
+use tracing::info;
+
+/// 获取所有预设
+///
+/// # 返回值
+/// - 成功: 返回按名称排序的预设列表
+pub async fn list(State(state): State<AppState>) -> anyhow::Result<Resp<Vec<Preset>>, Resp<()>> {
+    let presets = state.presets.read().await.values().cloned().collect();
+    Ok(response::success_data(presets))
+}
+
+/// 获取单个预设
+///
+/// # 参数
+/// - `name`: 预设名称
+///
+/// # 返回值
+/// - 成功: 返回预设内容
+/// - 失败: 预设不存在
+pub async fn get(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> anyhow::Result<Resp<Preset>, Resp<()>> {
+    let preset = state
+        .presets
+        .read()
+        .await
+        .get(&name)
+        .cloned()
+        .ok_or_else(|| not_found(&name))?;
+
+    Ok(response::success_data(preset))
+}
+
+/// 创建预设
+///
+/// # 参数
+/// - `req`: 预设名称和IP段列表
+///
+/// # 返回值
+/// - 成功: 返回创建的预设
+/// - 失败: 参数错误或预设已存在
+pub async fn create(
+    State(state): State<AppState>,
+    Json(req): Json<CreateRequest>,
+) -> anyhow::Result<Resp<Preset>, Resp<()>> {
+    let name = req.name.trim().to_string();
+    if name.is_empty() {
+        return Err(response::fail(
+            Code::InvalidParameter,
+            "preset name is empty".to_string(),
+        ));
+    }
+    let ranges = validate(req.ranges)?;
+
+    let now = Local::now();
+    let preset = Preset {
+        name: name.clone(),
+        ranges,
+        created_at: now,
+        updated_at: now,
+    };
+    let created = state
+        .presets
+        .update(|presets| {
+            if presets.contains_key(&name) {
+                return false;
+            }
+            presets.insert(name.clone(), preset.clone());
+            true
+        })
+        .await?;
+    if !created {
+        return Err(response::fail(
+            Code::Conflict,
+            format!("preset already exists: {}", name),
+        ));
+    }
+
+    info!("preset created: {} ({} ranges)", name, preset.ranges.len());
+    Ok(response::success_data(preset))
+}
+
+/// 修改预设的IP段列表
+///
+/// # 参数
+/// - `name`: 预设名称
+/// - `req`: 新的IP段列表
+///
+/// # 返回值
+/// - 成功: 返回修改后的预设
+/// - 失败: 参数错误或预设不存在
+pub async fn update(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(req): Json<UpdateRequest>,
+) -> anyhow::Result<Resp<Preset>, Resp<()>> {
+    let ranges = validate(req.ranges)?;
+
+    let preset = state
+        .presets
+        .update(|presets| {
+            presets.get_mut(&name).map(|preset| {
+                preset.ranges = ranges;
+                preset.updated_at = Local::now();
+                preset.clone()
+            })
+        })
+        .await?
+        .ok_or_else(|| not_found(&name))?;
+
+    info!("preset updated: {} ({} ranges)", name, preset.ranges.len());
+    Ok(response::success_data(preset))
+}
+
+/// 删除预设
+///
+/// # 参数
+/// - `name`: 预设名称
+///
+/// # 返回值
+/// - 成功: 返回成功响应
+/// - 失败: 预设不存在
+pub async fn delete(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> anyhow::Result<Resp<()>, Resp<()>> {
+    state
+        .presets
+        .update(|presets| presets.remove(&name))
+        .await?
+        .ok_or_else(|| not_found(&name))?;
+
+    info!("preset deleted: {}", name);
+    Ok(response::success())
+}
+
+/// 获取预设中的IP段列表，供优选任务使用
+///
+/// 预设文件可能被手动修改，使用前同样校验并去重
+///
+/// # 参数
+/// - `name`: 预设名称
+///
+/// # 返回值
+/// - 成功: 返回去重后的IP段列表
+/// - 失败: 预设不存在，或其中有格式错误的IP段
+pub async fn resolve(state: &AppState, name: &str) -> Result<Vec<String>, Resp<()>> {
+    let ranges = state
+        .presets
+        .read()
+        .await
+        .get(name)
+        .map(|preset| preset.ranges.clone())
+        .ok_or_else(|| not_found(name))?;
+
+    validate(ranges)
+}
+
+/// 校验并整理IP段列表
+///
+/// 去除空行和首尾空白，重复的IP段(如`1.1.1.1`与`1.1.1.1/32`)只保留第一个，
+/// 任一IP段格式错误时返回参数错误
+fn validate(ranges: Vec<String>) -> Result<Vec<String>, Resp<()>> {
+    let mut seen = HashSet::new();
+    let mut unique = Vec::new();
+    for range in ranges.iter().map(|range| range.trim()) {
+        if range.is_empty() {
+            continue;
+        }
+        let net = catalog::parse_range(range)
+            .map_err(|e| response::fail(Code::InvalidParameter, e.to_string()))?;
+        if seen.insert(net.trunc()) {
+            unique.push(range.to_string());
+        }
+    }
+    if unique.is_empty() {
+        return Err(response::fail(
+            Code::InvalidParameter,
+            "preset ranges is empty".to_string(),
+        ));
+    }
+
+    Ok(unique)
+}
+
+/// 构造预设不存在的错误响应
+fn not_found(name: &str) -> Resp<()> {
+    response::fail(Code::NotFound, format!("preset not found: {}", name))
+}
+
+/// 创建预设请求
+///
+/// # 字段
+/// - `name`: 预设名称
+/// - `ranges`: IP段列表
+#[derive(Deserialize)]
+pub struct CreateRequest {
+    pub name: String,
+    pub ranges: Vec<String>,
+}
+
+/// 修改预设请求
+///
+/// # 字段
+/// - `ranges`: 新的IP段列表
+#[derive(Deserialize)]
+pub struct UpdateRequest {
+    pub ranges: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configure::AppConfig;
+
+    /// 使用独立数据目录的应用状态
+    async fn state() -> AppState {
+        AppState::new(AppConfig::test("")).await.unwrap()
+    }
+
+    /// 构造IP段列表
+    fn ranges(ranges: &[&str]) -> Vec<String> {
+        ranges.iter().map(|range| range.to_string()).collect()
+    }
+
+    /// 创建预设，返回响应码
+    async fn create_preset(state: &AppState, name: &str, list: &[&str]) -> Code {
+        let req = CreateRequest {
+            name: name.to_string(),
+            ranges: ranges(list),
+        };
+        match create(State(state.clone()), Json(req)).await {
+            Ok(resp) => resp.code,
+            Err(resp) => resp.code,
+        }
+    }
+
+    /// 测试创建、覆盖修改和删除预设
+    #[tokio::test]
+    async fn test_crud() {
+        let state = state().await;
+        let created = create_preset(&state, " office ", &["1.1.1.0/24"]).await;
+        assert_eq!(created, Code::Success);
+        let existed = create_preset(&state, "office", &["2.2.2.0/24"]).await;
+        assert_eq!(existed, Code::Conflict);
+
+        let req = UpdateRequest {
+            ranges: ranges(&["2.2.2.0/24", "3.3.3.3"]),
+        };
+        let updated = update(State(state.clone()), Path("office".to_string()), Json(req))
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(updated.ranges, ranges(&["2.2.2.0/24", "3.3.3.3"]));
+        assert!(updated.updated_at >= updated.created_at);
+
+        // 修改结果已持久化，重新打开后仍然生效
+        let reopened = AppState::new((*state.config).clone()).await.unwrap();
+        let preset = get(State(reopened), Path("office".to_string()))
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(preset.ranges, updated.ranges);
+
+        let req = UpdateRequest {
+            ranges: ranges(&["1.1.1.0/24"]),
+        };
+        let missing = update(State(state.clone()), Path("home".to_string()), Json(req)).await;
+        assert_eq!(missing.unwrap_err().code, Code::NotFound);
+
+        delete(State(state.clone()), Path("office".to_string()))
+            .await
+            .unwrap();
+        let deleted = delete(State(state.clone()), Path("office".to_string())).await;
+        assert_eq!(deleted.unwrap_err().code, Code::NotFound);
+        assert!(list(State(state)).await.unwrap().data.unwrap().is_empty());
+    }
+
+    /// 测试格式错误或为空的IP段列表被拒绝，且不会保存预设
+    #[tokio::test]
+    async fn test_create_invalid() {
+        let state = state().await;
+        let invalid = create_preset(&state, "office", &["1.1.1.0/24", "not-a-cidr"]).await;
+        assert_eq!(invalid, Code::InvalidParameter);
+        let empty = create_preset(&state, "office", &[" ", ""]).await;
+        assert_eq!(empty, Code::InvalidParameter);
+        let unnamed = create_preset(&state, " ", &["1.1.1.0/24"]).await;
+        assert_eq!(unnamed, Code::InvalidParameter);
+        assert!(state.presets.read().await.is_empty());
+    }
+
+    /// 测试将预设解析为IP段列表时去除重复的IP段，并拒绝手动写入的非法IP段
+    #[tokio::test]
+    async fn test_resolve() {
+        let state = state().await;
+        let list = [
+            " 1.1.1.0/24",
+            "1.1.1.1",
+            "1.1.1.0/24",
+            "1.1.1.1/32",
+            "",
+            "1.1.1.7/24",
+        ];
+        assert_eq!(create_preset(&state, "office", &list).await, Code::Success);
+        assert_eq!(
+            resolve(&state, "office").await.unwrap(),
+            ranges(&["1.1.1.0/24", "1.1.1.1"])
+        );
+        assert_eq!(
+            resolve(&state, "home").await.unwrap_err().code,
+            Code::NotFound
+        );
+
+        let now = Local::now();
+        state
+            .presets
+            .update(|presets| {
+                presets.insert(
+                    "edited".to_string(),
+                    Preset {
+                        name: "edited".to_string(),
+                        ranges: ranges(&["1.1.1.0/24", "1.1.1.0/33"]),
+                        created_at: now,
+                        updated_at: now,
+                    },
+                )
+            })
+            .await
+            .unwrap();
+        let invalid = resolve(&state, "edited").await.unwrap_err();
+        assert_eq!(invalid.code, Code::InvalidParameter);
+    }
+}
@@ -0,0 +1,180 @@
+//! Cloudflare IP段目录模块
+//!
+//! 内置一份Cloudflare官方公布的IPv4/IPv6段列表，并支持：
+//! - 从配置的URL在线刷新
+//! - 从本地文件离线刷新
+//!
+//! 刷新后的列表保存在数据目录中，重启后继续生效。
+//! 升级后内置列表比保存的列表更新时，改用内置列表
+
+use chrono::{DateTime, Local};
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// 内置IPv4段列表
+const BUILTIN_V4: &str = include_str!("ips-v4.txt");
+/// 内置IPv6段列表
+const BUILTIN_V6: &str = include_str!("ips-v6.txt");
+/// 内置列表的更新时间，修改ips-v4.txt、ips-v6.txt时需要同步修改
+const BUILTIN_UPDATED_AT: &str = "2026-10-19T00:00:00+00:00";
+
+/// IP段目录
+///
+/// # 字段
+/// - `v4`: IPv4段列表
+/// - `v6`: IPv6段列表
+/// - `source`: 列表来源(builtin、URL或文件路径)
+/// - `updated_at`: 列表更新时间(内置列表为随程序发布的列表时间，刷新的列表为刷新时间)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Catalog {
+    pub v4: Vec<String>,
+    pub v6: Vec<String>,
+    pub source: String,
+    pub updated_at: Option<DateTime<Local>>,
+}
+
+/// 默认使用内置列表
+impl Default for Catalog {
+    fn default() -> Self {
+        Self {
+            v4: lines(BUILTIN_V4).map(str::to_string).collect(),
+            v6: lines(BUILTIN_V6).map(str::to_string).collect(),
+            source: "builtin".to_string(),
+            updated_at: Some(builtin_updated_at()),
+        }
+    }
+}
+
+/// 目录中的地址族
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Family {
+    /// 全部
+    All,
+    /// 仅IPv4
+    V4,
+    /// 仅IPv6
+    V6,
+}
+
+impl Catalog {
+    /// 解析每行一个IP段的纯文本
+    ///
+    /// 忽略空行和`#`开头的注释行，单个IP视为/32或/128
+    ///
+    /// # 参数
+    /// - `text`: 待解析文本
+    /// - `source`: 文本来源
+    ///
+    /// # 返回值
+    /// - 成功: 返回新的目录
+    /// - 失败: 存在无法解析的行，或解析结果为空
+    pub fn parse(text: &str, source: &str) -> anyhow::Result<Self> {
+        let mut catalog = Self {
+            v4: Vec::new(),
+            v6: Vec::new(),
+            source: source.to_string(),
+            updated_at: Some(Local::now()),
+        };
+
+        for line in lines(text) {
+            let net = parse_range(line)?;
+            match net {
+                IpNet::V4(_) => catalog.v4.push(net.to_string()),
+                IpNet::V6(_) => catalog.v6.push(net.to_string()),
+            }
+        }
+
+        if catalog.v4.is_empty() && catalog.v6.is_empty() {
+            return Err(anyhow::anyhow!("no ip range found in {}", source));
+        }
+
+        Ok(catalog)
+    }
+
+    /// 判断列表是否比内置列表旧
+    ///
+    /// 旧版本保存的内置列表没有更新时间，同样视为比内置列表旧
+    pub fn is_outdated(&self) -> bool {
+        self.updated_at
+            .is_none_or(|updated_at| updated_at < builtin_updated_at())
+    }
+
+    /// 获取指定地址族的IP段列表
+    pub fn ranges(&self, family: Family) -> Vec<String> {
+        match family {
+            Family::All => self.v4.iter().chain(&self.v6).cloned().collect(),
+            Family::V4 => self.v4.clone(),
+            Family::V6 => self.v6.clone(),
+        }
+    }
+}
+
+/// 解析单个IP段
+///
+/// 支持CIDR格式和单个IP(视为/32或/128)
+///
+/// # 返回值
+/// - 成功: 返回解析后的IP段
+/// - 失败: 格式错误
+pub fn parse_range(range: &str) -> anyhow::Result<IpNet> {
+    let range = range.trim();
+    range
+        .parse::<IpNet>()
+        .or_else(|_| range.parse::<IpAddr>().map(IpNet::from))
+        .map_err(|_| anyhow::anyhow!("invalid ip range: {}", range))
+}
+
+/// 内置列表的更新时间
+fn builtin_updated_at() -> DateTime<Local> {
+    DateTime::parse_from_rfc3339(BUILTIN_UPDATED_AT)
+        .expect("invalid builtin catalog time")
+        .with_timezone(&Local)
+}
+
+/// 遍历文本中的有效行
+fn lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试内置列表可以被正确解析
+    #[test]
+    fn test_builtin_is_valid() {
+        let builtin = Catalog::default();
+        let text = builtin.ranges(Family::All).join("\n");
+        let parsed = Catalog::parse(&text, "builtin").unwrap();
+        assert_eq!(parsed.v4, builtin.v4);
+        assert_eq!(parsed.v6, builtin.v6);
+    }
+
+    /// 测试解析时忽略注释并拒绝非法行
+    #[test]
+    fn test_parse() {
+        let catalog = Catalog::parse("# comment\n1.1.1.1\n\n2606:4700::/32\n", "test").unwrap();
+        assert_eq!(catalog.v4, vec!["1.1.1.1/32"]);
+        assert_eq!(catalog.v6, vec!["2606:4700::/32"]);
+        assert!(Catalog::parse("not an ip", "test").is_err());
+    }
+
+    /// 测试早于内置列表的刷新结果和旧版本保存的内置列表视为过期
+    #[test]
+    fn test_is_outdated() {
+        assert!(!Catalog::default().is_outdated());
+
+        let mut catalog = Catalog::parse("1.1.1.1\n", "test").unwrap();
+        assert!(!catalog.is_outdated());
+
+        catalog.updated_at = Some(builtin_updated_at() - chrono::Duration::days(1));
+        assert!(catalog.is_outdated());
+
+        catalog.updated_at = None;
+        assert!(catalog.is_outdated());
+    }
+}
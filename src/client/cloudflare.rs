@@ -0,0 +1,39 @@
+//! Cloudflare公开数据客户端模块
+//!
+//! 提供从Cloudflare获取官方IP段列表的功能
+
+use std::sync::LazyLock;
+
+// 全局HTTP客户端，使用LazyLock确保线程安全初始化
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .build()
+        .expect("Failed to create reqwest client")
+});
+
+/// 获取IP段列表
+///
+/// # 参数
+/// - `urls`: IP段列表地址，每个地址返回每行一个IP段的纯文本
+///
+/// # 返回值
+/// - 成功: 返回所有地址内容拼接后的文本
+/// - 失败: 任一地址请求失败
+pub async fn fetch_ranges(urls: &[String]) -> anyhow::Result<String> {
+    let mut text = String::new();
+    for url in urls {
+        let response = CLIENT.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Failed to fetch ip ranges from {}: {}",
+                url,
+                response.status()
+            ));
+        }
+
+        text.push_str(&response.text().await?);
+        text.push('\n');
+    }
+
+    Ok(text)
+}
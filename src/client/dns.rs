@@ -0,0 +1,425 @@
+//! DNS查询模块
+//!
+//! 直接向指定DNS服务器发送查询(UDP或TCP)，用于验证DNS记录更新后是否已生效：
+//! - UDP响应被截断时自动改用TCP重新查询
+//! - 按间隔轮询所有DNS服务器，直到都返回新值或超过最长验证时间
+
+use crate::configure::propagation::{PropagationConfig, Transport};
+use crate::model::dns::{Propagation, PropagationStatus, ServerAnswer};
+use anyhow::{anyhow, bail};
+use hickory_proto::op::{Message, MessageType, OpCode, Query, ResponseCode};
+use hickory_proto::rr::{Name, RData, RecordType};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::task::JoinSet;
+use tracing::{debug, info};
+
+/// UDP响应的最大长度
+const MAX_UDP_SIZE: usize = 4096;
+
+/// 查询到的记录
+///
+/// # 字段
+/// - `value`: 记录值
+/// - `ttl`: TTL(秒)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub value: String,
+    pub ttl: u32,
+}
+
+/// 等待DNS记录在所有配置的DNS服务器上生效
+///
+/// # 参数
+/// - `config`: 传播验证配置
+/// - `host`: 记录主机名
+/// - `record_type`: 记录类型
+/// - `expected`: 新的记录值
+///
+/// # 返回值
+/// 返回验证结果，DNS服务器配置错误等无法验证的情况也记录在结果中
+pub async fn wait_propagation(
+    config: &PropagationConfig,
+    host: &str,
+    record_type: &str,
+    expected: IpAddr,
+) -> Propagation {
+    let start = Instant::now();
+    let result = poll(config, host, record_type, expected, start).await;
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+
+    match result {
+        Ok((status, servers)) => Propagation {
+            status,
+            elapsed_ms,
+            error: None,
+            servers,
+        },
+        Err(e) => Propagation {
+            status: PropagationStatus::Failed,
+            elapsed_ms,
+            error: Some(e.to_string()),
+            servers: Vec::new(),
+        },
+    }
+}
+
+/// 轮询所有DNS服务器，直到都返回新值或下一轮查询将超过最长验证时间
+///
+/// 已返回新值的服务器不再查询
+async fn poll(
+    config: &PropagationConfig,
+    host: &str,
+    record_type: &str,
+    expected: IpAddr,
+    start: Instant,
+) -> anyhow::Result<(PropagationStatus, Vec<ServerAnswer>)> {
+    let servers = config.servers()?;
+    if servers.is_empty() {
+        bail!("no nameservers configured");
+    }
+    let mut name = Name::from_ascii(host)?;
+    name.set_fqdn(true);
+    let record_type = RecordType::from_str(record_type)?;
+    let deadline = start + config.deadline();
+
+    let mut answers: Vec<ServerAnswer> = servers
+        .iter()
+        .map(|server| ServerAnswer {
+            server: server.to_string(),
+            propagated: false,
+            values: Vec::new(),
+            error: None,
+        })
+        .collect();
+
+    loop {
+        let timeout = config
+            .timeout()
+            .min(deadline.saturating_duration_since(Instant::now()));
+        let mut tasks = JoinSet::new();
+        for (i, &server) in servers.iter().enumerate() {
+            if answers[i].propagated {
+                continue;
+            }
+            let name = name.clone();
+            let transport = config.transport;
+            tasks.spawn(async move {
+                (
+                    i,
+                    query(server, &name, record_type, transport, timeout, false).await,
+                )
+            });
+        }
+
+        while let Some(joined) = tasks.join_next().await {
+            let (i, result) = joined?;
+            let answer = &mut answers[i];
+            match result {
+                Ok(values) => {
+                    answer.propagated = values
+                        .iter()
+                        .any(|value| value.value.parse::<IpAddr>().ok() == Some(expected));
+                    answer.values = values.into_iter().map(|value| value.value).collect();
+                    answer.error = None;
+                }
+                Err(e) => {
+                    debug!("query {} failed: {}", answer.server, e);
+                    answer.error = Some(e.to_string());
+                }
+            }
+        }
+
+        let pending = answers.iter().filter(|answer| !answer.propagated).count();
+        if pending == 0 {
+            return Ok((PropagationStatus::Propagated, answers));
+        }
+        if deadline.saturating_duration_since(Instant::now()) <= config.interval() {
+            return Ok((PropagationStatus::TimedOut, answers));
+        }
+        info!(
+            "{} not yet {} on {} of {} nameservers, retrying",
+            host,
+            expected,
+            pending,
+            answers.len()
+        );
+        tokio::time::sleep(config.interval()).await;
+    }
+}
+
+/// 向DNS服务器查询记录
+///
+/// # 参数
+/// - `server`: DNS服务器地址
+/// - `name`: 查询的域名
+/// - `record_type`: 记录类型
+/// - `transport`: 传输协议，UDP响应被截断时改用TCP
+/// - `timeout`: 查询超时(包括改用TCP后的查询)
+/// - `authoritative`: 是否要求权威应答(不请求递归，响应必须带AA标志)
+///
+/// # 返回值
+/// - 成功: 返回该类型的记录，域名不存在时为空
+/// - 失败: 网络错误、超时、DNS服务器返回错误或要求权威应答时服务器不是权威服务器
+pub async fn query(
+    server: SocketAddr,
+    name: &Name,
+    record_type: RecordType,
+    transport: Transport,
+    timeout: Duration,
+    authoritative: bool,
+) -> anyhow::Result<Vec<Answer>> {
+    let id = message_id();
+    let mut message = Message::new();
+    message
+        .set_id(id)
+        .set_message_type(MessageType::Query)
+        .set_op_code(OpCode::Query)
+        .set_recursion_desired(!authoritative)
+        .add_query(Query::query(name.clone(), record_type));
+    let request = message.to_vec()?;
+
+    let response = tokio::time::timeout(timeout, async {
+        let response = match transport {
+            Transport::Udp => exchange_udp(server, &request, id).await?,
+            Transport::Tcp => Message::from_vec(&exchange_tcp(server, &request).await?)?,
+        };
+        if transport == Transport::Udp && response.truncated() {
+            debug!("response from {} truncated, retrying over tcp", server);
+            return Ok(Message::from_vec(&exchange_tcp(server, &request).await?)?);
+        }
+        anyhow::Ok(response)
+    })
+    .await
+    .map_err(|_| anyhow!("query {} timed out", server))??;
+
+    if response.id() != id {
+        bail!("response id from {} does not match the query", server);
+    }
+    let code = response.response_code();
+    if !matches!(code, ResponseCode::NoError | ResponseCode::NXDomain) {
+        bail!("{} answered {}", server, code);
+    }
+    // 递归解析器的缓存可能是旧值
+    if authoritative && !response.authoritative() {
+        bail!("{} is not authoritative for {}", server, name);
+    }
+    if code == ResponseCode::NXDomain {
+        return Ok(Vec::new());
+    }
+
+    Ok(response
+        .answers()
+        .iter()
+        .filter(|record| record.record_type() == record_type)
+        .filter_map(|record| {
+            let value = match record.data() {
+                RData::A(a) => a.to_string(),
+                RData::AAAA(aaaa) => aaaa.to_string(),
+                _ => return None,
+            };
+            Some(Answer {
+                value,
+                ttl: record.ttl(),
+            })
+        })
+        .collect())
+}
+
+/// 生成随机的报文ID
+pub fn message_id() -> u16 {
+    rand::random()
+}
+
+/// 通过UDP发送查询，忽略ID不匹配的响应
+async fn exchange_udp(server: SocketAddr, request: &[u8], id: u16) -> anyhow::Result<Message> {
+    let local: SocketAddr = if server.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(local).await?;
+    socket.connect(server).await?;
+    socket.send(request).await?;
+
+    let mut buf = vec![0; MAX_UDP_SIZE];
+    loop {
+        let len = socket.recv(&mut buf).await?;
+        let response = Message::from_vec(&buf[..len])?;
+        if response.id() == id {
+            return Ok(response);
+        }
+    }
+}
+
+/// 通过TCP发送报文，报文前加两字节长度
+///
+/// # 返回值
+/// - 成功: 返回响应报文(不含长度)
+/// - 失败: 网络错误
+pub async fn exchange_tcp(server: SocketAddr, request: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut stream = TcpStream::connect(server).await?;
+    stream.write_u16(request.len() as u16).await?;
+    stream.write_all(request).await?;
+
+    let len = stream.read_u16().await?;
+    let mut buf = vec![0; usize::from(len)];
+    stream.read_exact(&mut buf).await?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hickory_proto::rr::Record;
+    use hickory_proto::rr::rdata::A;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::net::TcpListener;
+
+    /// 生成对查询的响应，第n次查询返回`answers[n]`(超出时返回最后一个)
+    fn respond(
+        request: &[u8],
+        answers: &[Ipv4Addr],
+        count: &AtomicUsize,
+        truncated: bool,
+        authoritative: bool,
+    ) -> Vec<u8> {
+        let request = Message::from_vec(request).unwrap();
+        let n = count.fetch_add(1, Ordering::SeqCst).min(answers.len() - 1);
+        let query = request.queries()[0].clone();
+
+        let mut response = Message::new();
+        response
+            .set_id(request.id())
+            .set_message_type(MessageType::Response)
+            .set_op_code(OpCode::Query)
+            .set_truncated(truncated)
+            .set_authoritative(authoritative);
+        if !truncated {
+            response.add_answer(Record::from_rdata(
+                query.name().clone(),
+                60,
+                RData::A(A(answers[n])),
+            ));
+        }
+        response.add_query(query);
+        response.to_vec().unwrap()
+    }
+
+    /// 启动本地DNS服务器(同一端口监听UDP和TCP)
+    ///
+    /// # 参数
+    /// - `answers`: 依次返回的A记录值
+    /// - `truncated`: UDP响应是否截断
+    /// - `authoritative`: 响应是否带AA标志
+    async fn serve(answers: Vec<Ipv4Addr>, truncated: bool, authoritative: bool) -> SocketAddr {
+        let udp = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = udp.local_addr().unwrap();
+        let tcp = TcpListener::bind(addr).await.unwrap();
+        let answers = Arc::new(answers);
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let (udp_answers, udp_count) = (answers.clone(), count.clone());
+        tokio::spawn(async move {
+            let mut buf = vec![0; MAX_UDP_SIZE];
+            loop {
+                let (len, peer) = udp.recv_from(&mut buf).await.unwrap();
+                let response = respond(
+                    &buf[..len],
+                    &udp_answers,
+                    &udp_count,
+                    truncated,
+                    authoritative,
+                );
+                udp.send_to(&response, peer).await.unwrap();
+            }
+        });
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = tcp.accept().await.unwrap();
+                let len = stream.read_u16().await.unwrap();
+                let mut buf = vec![0; usize::from(len)];
+                stream.read_exact(&mut buf).await.unwrap();
+                let response = respond(&buf, &answers, &count, false, authoritative);
+                stream.write_u16(response.len() as u16).await.unwrap();
+                stream.write_all(&response).await.unwrap();
+            }
+        });
+
+        addr
+    }
+
+    fn config(server: SocketAddr, deadline_secs: u64) -> PropagationConfig {
+        PropagationConfig {
+            enabled: true,
+            nameservers: vec![server.to_string()],
+            transport: Transport::Udp,
+            timeout_ms: 500,
+            interval_ms: 10,
+            deadline_secs,
+        }
+    }
+
+    /// 测试通过UDP和TCP查询，以及UDP响应截断时改用TCP
+    #[tokio::test]
+    async fn test_query() {
+        let name = Name::from_ascii("t.example.xyz.").unwrap();
+        let ip = Ipv4Addr::new(104, 16, 1, 1);
+        let timeout = Duration::from_secs(1);
+
+        let server = serve(vec![ip], false, false).await;
+        for transport in [Transport::Udp, Transport::Tcp] {
+            let values = query(server, &name, RecordType::A, transport, timeout, false)
+                .await
+                .unwrap();
+            assert_eq!(
+                values,
+                vec![Answer {
+                    value: ip.to_string(),
+                    ttl: 60
+                }]
+            );
+        }
+
+        let server = serve(vec![ip], true, false).await;
+        let values = query(server, &name, RecordType::A, Transport::Udp, timeout, false)
+            .await
+            .unwrap();
+        assert_eq!(values[0].value, ip.to_string());
+
+        // 要求权威应答时拒绝不带AA标志的响应
+        let result = query(server, &name, RecordType::A, Transport::Udp, timeout, true).await;
+        assert!(result.is_err());
+        let server = serve(vec![ip], false, true).await;
+        let values = query(server, &name, RecordType::A, Transport::Udp, timeout, true)
+            .await
+            .unwrap();
+        assert_eq!(values[0].value, ip.to_string());
+    }
+
+    /// 测试等待新值生效及超时
+    #[tokio::test]
+    async fn test_wait_propagation() {
+        let old = Ipv4Addr::new(104, 16, 1, 1);
+        let new = Ipv4Addr::new(104, 16, 1, 2);
+
+        let server = serve(vec![old, old, new], false, false).await;
+        let result = wait_propagation(&config(server, 5), "t.example.xyz", "A", new.into()).await;
+        assert_eq!(result.status, PropagationStatus::Propagated);
+        assert_eq!(result.servers[0].values, vec![new.to_string()]);
+
+        let server = serve(vec![old], false, false).await;
+        let result = wait_propagation(&config(server, 1), "t.example.xyz", "A", new.into()).await;
+        assert_eq!(result.status, PropagationStatus::TimedOut);
+        assert_eq!(result.servers[0].values, vec![old.to_string()]);
+
+        let mut invalid = config(server, 0);
+        invalid.nameservers = vec!["ns.example.xyz".to_string()];
+        let result = wait_propagation(&invalid, "t.example.xyz", "A", new.into()).await;
+        assert_eq!(result.status, PropagationStatus::Failed);
+    }
+}
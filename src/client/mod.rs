@@ -1,6 +1,18 @@
 //! 客户端模块
 //!
 //! 包含与外部服务交互的客户端实现：
+//! - `cloudflare`: Cloudflare公开数据客户端
+//! - `dns`: DNS查询(验证记录更新后是否生效)
 //! - `namesilo`: Namesilo DNS服务客户端
+//! - `probe`: TCP连接探测
+//! - `provider`: 按配置选择DNS服务商
+//! - `rfc2136`: RFC 2136动态更新客户端
+//! - `webhook`: Webhook DNS服务商客户端
 
+pub mod cloudflare;
+pub mod dns;
 pub mod namesilo;
+pub mod probe;
+pub mod provider;
+pub mod rfc2136;
+pub mod webhook;
@@ -3,19 +3,39 @@
 //! 提供与Namesilo DNS API交互的功能，包括:
 //! - DNS记录更新
 //! - DNS记录列表查询
+//! - 查找配置中的目标记录
+//! - 将Namesilo返回码映射为业务错误
 
 use crate::configure::namesilo::NamesiloConfig;
-use anyhow::Ok;
-use serde::Deserialize;
-use serde_repr::{Deserialize_repr, Serialize_repr};
-use std::sync::LazyLock;
-
-// 全局HTTP客户端，使用LazyLock确保线程安全初始化
-static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
-    reqwest::Client::builder()
-        .build()
-        .expect("Failed to create reqwest client")
-});
+use crate::model::response::Code;
+use reqwest::StatusCode;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+// 全局HTTP客户端，按建立连接超时分别创建，请求超时在每次请求时设置
+static CLIENTS: LazyLock<Mutex<HashMap<Duration, reqwest::Client>>> =
+    LazyLock::new(Default::default);
+
+/// 获取使用配置中建立连接超时的HTTP客户端
+fn client(config: &NamesiloConfig) -> reqwest::Client {
+    CLIENTS
+        .lock()
+        .unwrap()
+        .entry(config.connect_timeout())
+        .or_insert_with(|| {
+            reqwest::Client::builder()
+                .connect_timeout(config.connect_timeout())
+                .build()
+                .expect("Failed to create reqwest client")
+        })
+        .clone()
+}
 
 /// 更新DNS记录
 ///
@@ -23,39 +43,29 @@ static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
 /// - `config`: Namesilo配置信息
 /// - `ip`: 要更新的IP地址
 /// - `rrid`: 记录ID
+/// - `ttl`: 记录TTL
 ///
 /// # 返回值
 /// - 成功: Ok(())
 /// - 失败: 返回错误信息
-pub async fn dns_update(config: &NamesiloConfig, ip: &str, rrid: &str) -> anyhow::Result<()> {
-    let mut url = reqwest::Url::parse(&config.url)?;
-    url.set_path("/api/dnsUpdateRecord");
-    url.query_pairs_mut().extend_pairs([
-        ("version", "1"),
-        ("type", "json"),
-        ("key", config.key.as_str()),
-        ("domain", config.domain.as_str()),
-        ("rrid", rrid),
-        ("rrhost", config.rrhost.as_str()),
-        ("rrvalue", ip),
-        ("rrttl", config.rrttl.as_str()),
-    ]);
-
-    let response = CLIENT.get(url).send().await?;
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!(
-            "Failed to update DNS record: {}",
-            response.status()
-        ));
-    }
-
-    let nr: NamesiloResponse<DnsUpdateReply> = response.json().await?;
-    if nr.reply.code != ResponseCode::Success {
-        return Err(anyhow::anyhow!(
-            "Failed to update DNS record: {}",
-            nr.reply.detail
-        ));
-    }
+pub async fn dns_update(
+    config: &NamesiloConfig,
+    ip: &str,
+    rrid: &str,
+    ttl: u32,
+) -> anyhow::Result<()> {
+    let ttl = ttl.to_string();
+    let _: IgnoredAny = request(
+        config,
+        "dnsUpdateRecord",
+        &[
+            ("rrid", rrid),
+            ("rrhost", config.rrhost.as_str()),
+            ("rrvalue", ip),
+            ("rrttl", ttl.as_str()),
+        ],
+    )
+    .await?;
 
     Ok(())
 }
@@ -69,32 +79,166 @@ pub async fn dns_update(config: &NamesiloConfig, ip: &str, rrid: &str) -> anyhow
 /// - 成功: 返回资源记录列表
 /// - 失败: 返回错误信息
 pub async fn dns_list(config: &NamesiloConfig) -> anyhow::Result<Vec<ResourceRecord>> {
+    let reply: DnsListReply = request(config, "dnsListRecords", &[]).await?;
+
+    Ok(reply.resource_record)
+}
+
+/// 调用Namesilo API
+///
+/// 网络错误(包括超时)和5xx响应按指数退避加随机抖动重试，
+/// 其余HTTP错误和Namesilo业务错误不重试，每次尝试都会记录日志。
+/// 返回码不表示成功时返回`NamesiloError`
+///
+/// # 参数
+/// - `config`: Namesilo配置信息
+/// - `operation`: API操作名(如`dnsListRecords`)
+/// - `params`: 除公共参数外的查询参数
+///
+/// # 返回值
+/// - 成功: 返回解析后的`reply`
+/// - 失败: 重试耗尽、业务错误或响应无法解析
+async fn request<Reply: DeserializeOwned>(
+    config: &NamesiloConfig,
+    operation: &str,
+    params: &[(&str, &str)],
+) -> anyhow::Result<Reply> {
     let mut url = reqwest::Url::parse(&config.url)?;
-    url.set_path("/api/dnsListRecords");
-    url.query_pairs_mut().extend_pairs([
-        ("version", "1"),
-        ("type", "json"),
-        ("key", config.key.as_str()),
-        ("domain", config.domain.as_str()),
-    ]);
+    url.set_path(&format!("/api/{}", operation));
+    url.query_pairs_mut()
+        .extend_pairs([
+            ("version", "1"),
+            ("type", "json"),
+            ("key", config.key.as_str()),
+            ("domain", config.domain.as_str()),
+        ])
+        .extend_pairs(params);
+
+    let attempts = config.retries + 1;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let started = Instant::now();
+        let error = match send(config, operation, url.clone()).await {
+            Attempt::Done(body) => {
+                info!(
+                    "namesilo {} attempt {}/{} succeeded in {}ms",
+                    operation,
+                    attempt,
+                    attempts,
+                    started.elapsed().as_millis()
+                );
+                // 先只解析返回码，失败的回复中可能缺少操作相关的字段
+                let status: NamesiloResponse<ReplyStatus> = serde_json::from_slice(&body)?;
+                if !status.reply.code.is_success() {
+                    return Err(NamesiloError::Reply {
+                        operation: operation.to_string(),
+                        code: status.reply.code,
+                        detail: status.reply.detail,
+                    }
+                    .into());
+                }
 
-    let response = CLIENT.get(url).send().await?;
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!(
-            "Failed to list DNS record: {}",
-            response.status()
-        ));
+                let nr: NamesiloResponse<Reply> = serde_json::from_slice(&body)?;
+                return Ok(nr.reply);
+            }
+            Attempt::Fatal(e) => {
+                warn!(
+                    "namesilo {} attempt {}/{} failed: {}",
+                    operation, attempt, attempts, e
+                );
+                return Err(e);
+            }
+            Attempt::Retryable(e) => e,
+        };
+
+        if attempt >= attempts {
+            warn!(
+                "namesilo {} attempt {}/{} failed, giving up: {}",
+                operation, attempt, attempts, error
+            );
+            return Err(error);
+        }
+
+        let delay = jitter(config.backoff(attempt - 1));
+        warn!(
+            "namesilo {} attempt {}/{} failed, retrying in {}ms: {}",
+            operation,
+            attempt,
+            attempts,
+            delay.as_millis(),
+            error
+        );
+        tokio::time::sleep(delay).await;
     }
+}
 
-    let nr: NamesiloResponse<DnsListReply> = response.json().await?;
-    if nr.reply.code != ResponseCode::Success {
-        return Err(anyhow::anyhow!(
-            "Failed to list DNS record: {}",
-            nr.reply.detail
-        ));
+/// 单次请求的结果
+enum Attempt {
+    /// 请求成功，返回响应体
+    Done(Vec<u8>),
+    /// 可重试的错误(网络错误或5xx)
+    Retryable(anyhow::Error),
+    /// 不可重试的错误
+    Fatal(anyhow::Error),
+}
+
+/// 发送一次请求并读取响应体
+async fn send(config: &NamesiloConfig, operation: &str, url: reqwest::Url) -> Attempt {
+    // 去掉错误信息中的URL，避免泄露API Key
+    let response = match client(config)
+        .get(url)
+        .timeout(config.timeout())
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => return Attempt::Retryable(e.without_url().into()),
+    };
+
+    let status = response.status();
+    if status.is_server_error() {
+        return Attempt::Retryable(anyhow::anyhow!("server error: {}", status));
+    }
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Attempt::Fatal(
+            NamesiloError::RateLimited {
+                operation: operation.to_string(),
+            }
+            .into(),
+        );
+    }
+    if !status.is_success() {
+        return Attempt::Fatal(anyhow::anyhow!("unexpected status: {}", status));
     }
 
-    Ok(nr.reply.resource_record)
+    match response.bytes().await {
+        Ok(body) => Attempt::Done(body.to_vec()),
+        Err(e) => Attempt::Retryable(e.without_url().into()),
+    }
+}
+
+/// 在`[max/2, max]`范围内随机选取等待时间，避免多个客户端同时重试
+fn jitter(max: Duration) -> Duration {
+    let max = max.as_millis() as u64;
+    Duration::from_millis(rand::random_range(max / 2..=max))
+}
+
+/// 查找配置中的目标DNS记录(`rrhost.domain`)
+///
+/// # 参数
+/// - `config`: Namesilo配置信息
+///
+/// # 返回值
+/// - 成功: 返回目标记录，不存在时返回None
+/// - 失败: 返回错误信息
+pub async fn dns_find(config: &NamesiloConfig) -> anyhow::Result<Option<ResourceRecord>> {
+    let target_host = config.target_host();
+    let records = dns_list(config).await?;
+
+    Ok(records
+        .into_iter()
+        .find(|record| record.host == target_host))
 }
 
 #[derive(Deserialize, Debug)]
@@ -102,28 +246,469 @@ pub struct NamesiloResponse<Reply> {
     pub reply: Reply,
 }
 
+/// 所有回复共有的返回码和说明
 #[derive(Deserialize, Debug)]
-pub struct DnsUpdateReply {
+pub struct ReplyStatus {
     pub code: ResponseCode,
+    #[serde(default)]
     pub detail: String,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct DnsListReply {
-    pub code: ResponseCode,
-    pub detail: String,
     pub resource_record: Vec<ResourceRecord>,
 }
 
-#[derive(Deserialize, Debug)]
+/// DNS资源记录
+///
+/// # 字段
+/// - `record_id`: 记录ID
+/// - `record_type`: 记录类型(A、AAAA、CNAME等)
+/// - `host`: 完整主机名
+/// - `value`: 记录值
+/// - `ttl`: TTL(秒)
+#[derive(Deserialize, Debug, Clone)]
 pub struct ResourceRecord {
     pub record_id: String,
+    #[serde(rename = "type")]
+    pub record_type: String,
     pub host: String,
+    pub value: String,
+    #[serde(deserialize_with = "number_or_string")]
+    pub ttl: u32,
+}
+
+impl ResourceRecord {
+    /// 记录类型是否可以指向该IP(A记录对应IPv4，AAAA记录对应IPv6)
+    pub fn accepts(&self, ip: IpAddr) -> bool {
+        match self.record_type.as_str() {
+            "A" => ip.is_ipv4(),
+            "AAAA" => ip.is_ipv6(),
+            _ => false,
+        }
+    }
+}
+
+/// 解析数字或数字字符串(Namesilo部分版本以字符串返回数字字段)
+fn number_or_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(u32),
+        String(String),
+    }
+
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => Ok(n),
+        Value::String(s) => s.trim().parse().map_err(serde::de::Error::custom),
+    }
 }
 
-#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug, Default)]
-#[repr(u16)]
-pub enum ResponseCode {
-    #[default]
+/// Namesilo调用错误
+#[derive(Debug)]
+pub enum NamesiloError {
+    /// Namesilo返回了表示失败的返回码
+    Reply {
+        operation: String,
+        code: ResponseCode,
+        detail: String,
+    },
+    /// 请求过于频繁(HTTP 429)
+    RateLimited { operation: String },
+}
+
+impl NamesiloError {
+    /// 对应的API响应状态码
+    pub fn code(&self) -> Code {
+        match self {
+            NamesiloError::Reply { code, .. } => code.to_code(),
+            NamesiloError::RateLimited { .. } => Code::TooManyRequests,
+        }
+    }
+}
+
+impl Display for NamesiloError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NamesiloError::Reply {
+                operation,
+                code,
+                detail,
+            } => write!(
+                f,
+                "namesilo {} failed with code {}: {}",
+                operation,
+                code.value(),
+                detail
+            ),
+            NamesiloError::RateLimited { operation } => {
+                write!(f, "namesilo {} rate limited", operation)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NamesiloError {}
+
+/// 定义Namesilo返回码枚举及其与数值的相互转换
+macro_rules! response_codes {
+    ($($(#[$doc:meta])* $name:ident = $value:literal,)*) => {
+        /// Namesilo返回码
+        ///
+        /// 未收录的返回码解析为`Unknown`
+        #[derive(Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+        #[serde(from = "u16")]
+        pub enum ResponseCode {
+            $($(#[$doc])* $name,)*
+            /// 未收录的返回码
+            Unknown(u16),
+        }
+
+        impl From<u16> for ResponseCode {
+            fn from(value: u16) -> Self {
+                match value {
+                    $($value => ResponseCode::$name,)*
+                    other => ResponseCode::Unknown(other),
+                }
+            }
+        }
+
+        impl ResponseCode {
+            /// 返回码数值
+            pub fn value(self) -> u16 {
+                match self {
+                    $(ResponseCode::$name => $value,)*
+                    ResponseCode::Unknown(value) => value,
+                }
+            }
+        }
+    };
+}
+
+response_codes! {
+    /// 未使用HTTPS
+    HttpsNotUsed = 101,
+    /// 未指定版本
+    NoVersion = 102,
+    /// 无效的API版本
+    InvalidVersion = 103,
+    /// 未指定返回类型
+    NoType = 104,
+    /// 无效的返回类型
+    InvalidType = 105,
+    /// 未指定操作
+    NoOperation = 106,
+    /// 无效的操作
+    InvalidOperation = 107,
+    /// 缺少操作所需的参数
+    MissingParameters = 108,
+    /// 未指定API Key
+    NoApiKey = 109,
+    /// 无效的API Key
+    InvalidApiKey = 110,
+    /// 无效的用户
+    InvalidUser = 111,
+    /// 子账号不能使用API
+    SubAccountNotAllowed = 112,
+    /// 当前IP不允许访问该API账号
+    IpNotAllowed = 113,
+    /// 域名格式错误
+    InvalidDomainSyntax = 114,
+    /// 注册局无响应，稍后重试
+    RegistryNotResponding = 115,
+    /// 无效的沙箱账号
+    InvalidSandboxAccount = 116,
+    /// 信用卡资料不存在或不属于该账号
+    InvalidCreditCardProfile = 117,
+    /// 信用卡资料未验证
+    UnverifiedCreditCardProfile = 118,
+    /// 账户余额不足
+    InsufficientFunds = 119,
+    /// API Key必须通过GET传递
+    ApiKeyNotGet = 120,
+    /// 域名未激活或不属于该账号
+    DomainNotInAccount = 200,
+    /// Namesilo内部错误
+    InternalError = 201,
+    /// 一般错误(详见说明)
+    GeneralError = 210,
+    /// 已设置自动续费，未做修改
+    AlreadyAutoRenew = 250,
+    /// 已取消自动续费，未做修改
+    AlreadyNotAutoRenew = 251,
+    /// 已锁定，未做修改
+    AlreadyLocked = 252,
+    /// 已解锁，未做修改
+    AlreadyUnlocked = 253,
+    /// 无法修改域名服务器(详见说明)
+    NameServerUpdateFailed = 254,
+    /// 已开启隐私保护，未做修改
+    AlreadyPrivate = 255,
+    /// 已关闭隐私保护，未做修改
+    AlreadyNotPrivate = 256,
+    /// 域名处理错误(详见说明)
+    DomainProcessingError = 261,
+    /// 域名已在系统中激活
+    DomainAlreadyActive = 262,
+    /// 年数无效或未指定
+    InvalidYears = 263,
+    /// 不能按指定年数续费(详见说明)
+    RenewalNotAllowed = 264,
+    /// 当前不能转移域名(详见说明)
+    TransferNotAllowed = 265,
+    /// 该用户没有此域名转移
+    TransferNotFound = 266,
+    /// 域名无效或不可注册
+    InvalidDomainName = 267,
+    /// DNS修改错误(如记录不存在)
+    DnsModificationError = 280,
+    /// 操作成功
     Success = 300,
+    /// 注册成功，但部分域名服务器无效，已使用默认域名服务器
+    SuccessWithDefaultNameServers = 301,
+    /// 下单成功，但联系人信息有误，已使用默认联系人
+    SuccessWithDefaultContact = 302,
+    /// 已有请求正在处理，需要稍后重新提交
+    RequestInProgress = 400,
+}
+
+impl ResponseCode {
+    /// 是否表示操作成功
+    pub fn is_success(self) -> bool {
+        matches!(
+            self,
+            ResponseCode::Success
+                | ResponseCode::SuccessWithDefaultNameServers
+                | ResponseCode::SuccessWithDefaultContact
+        )
+    }
+
+    /// 转换为API响应状态码
+    pub fn to_code(self) -> Code {
+        use ResponseCode::*;
+        match self {
+            Success | SuccessWithDefaultNameServers | SuccessWithDefaultContact => Code::Success,
+            HttpsNotUsed
+            | NoVersion
+            | InvalidVersion
+            | NoType
+            | InvalidType
+            | NoOperation
+            | InvalidOperation
+            | MissingParameters
+            | InvalidDomainSyntax
+            | ApiKeyNotGet
+            | InvalidCreditCardProfile
+            | UnverifiedCreditCardProfile
+            | InvalidYears
+            | InvalidDomainName
+            | DnsModificationError => Code::InvalidParameter,
+            NoApiKey | InvalidApiKey | InvalidUser | InvalidSandboxAccount => Code::Unauthorized,
+            SubAccountNotAllowed | IpNotAllowed | InsufficientFunds => Code::Forbidden,
+            DomainNotInAccount | TransferNotFound => Code::NotFound,
+            AlreadyAutoRenew | AlreadyNotAutoRenew | AlreadyLocked | AlreadyUnlocked
+            | AlreadyPrivate | AlreadyNotPrivate | DomainAlreadyActive => Code::Conflict,
+            RequestInProgress => Code::TooManyRequests,
+            RegistryNotResponding
+            | InternalError
+            | GeneralError
+            | NameServerUpdateFailed
+            | DomainProcessingError
+            | RenewalNotAllowed
+            | TransferNotAllowed
+            | Unknown(_) => Code::BadGateway,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const LIST_OK: &str = r#"{"reply":{"code":300,"detail":"success","resource_record":[]}}"#;
+
+    /// 收到的请求(路径和查询参数)
+    pub type Requests = Arc<Mutex<Vec<String>>>;
+
+    /// 启动本地HTTP服务，按顺序返回指定的响应(超出时重复最后一个)
+    ///
+    /// 其他模块的测试也用它代替Namesilo API
+    pub async fn serve(responses: Vec<(u16, &'static str)>) -> (NamesiloConfig, Requests) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Requests::default();
+        let received = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 4096];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let head = String::from_utf8_lossy(&buf[..n]);
+                let target = head.split(' ').nth(1).unwrap_or_default().to_string();
+                let index = {
+                    let mut received = received.lock().unwrap();
+                    received.push(target);
+                    received.len() - 1
+                };
+                let (status, body) = responses[index.min(responses.len() - 1)];
+                let response = format!(
+                    "HTTP/1.1 {} X\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (config(&format!("http://{}", addr)), requests)
+    }
+
+    /// 测试用配置，重试等待时间很短
+    pub fn config(url: &str) -> NamesiloConfig {
+        NamesiloConfig {
+            url: url.to_string(),
+            key: "key".to_string(),
+            domain: "example.xyz".to_string(),
+            rrhost: "t".to_string(),
+            rrttl: "7207".to_string(),
+            connect_timeout_ms: 1000,
+            timeout_ms: 1000,
+            retries: 2,
+            backoff_ms: 1,
+            max_backoff_ms: 5,
+        }
+    }
+
+    /// 测试5xx响应重试后成功
+    #[tokio::test]
+    async fn test_retry_server_error() {
+        let (config, requests) = serve(vec![(500, ""), (502, ""), (200, LIST_OK)]).await;
+        assert!(dns_list(&config).await.unwrap().is_empty());
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    /// 测试重试耗尽后返回错误
+    #[tokio::test]
+    async fn test_retry_exhausted() {
+        let (config, requests) = serve(vec![(503, "")]).await;
+        assert!(dns_list(&config).await.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    /// 测试业务错误不重试，并保留返回码和说明
+    #[tokio::test]
+    async fn test_business_error() {
+        let (config, requests) = serve(vec![(
+            200,
+            r#"{"reply":{"code":110,"detail":"Invalid API Key"}}"#,
+        )])
+        .await;
+        let err = dns_list(&config).await.unwrap_err();
+        let err = err.downcast_ref::<NamesiloError>().unwrap();
+        assert_eq!(err.code(), Code::Unauthorized);
+        assert!(err.to_string().contains("Invalid API Key"));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        let (config, _) = serve(vec![(429, "")]).await;
+        let err = dns_list(&config).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<NamesiloError>().unwrap().code(),
+            Code::TooManyRequests
+        );
+    }
+
+    /// 测试返回码解析，未收录的返回码不会导致解析失败
+    #[test]
+    fn test_response_code() {
+        let status: ReplyStatus =
+            serde_json::from_str(r#"{"code":280,"detail":"record not found"}"#).unwrap();
+        assert_eq!(status.code, ResponseCode::DnsModificationError);
+        assert_eq!(status.code.to_code(), Code::InvalidParameter);
+
+        let code: ResponseCode = serde_json::from_str("999").unwrap();
+        assert_eq!(code, ResponseCode::Unknown(999));
+        assert_eq!(code.value(), 999);
+        assert_eq!(code.to_code(), Code::BadGateway);
+        assert!(ResponseCode::from(301).is_success());
+    }
+
+    /// 测试解析完整的资源记录，数字字段兼容字符串格式
+    #[tokio::test]
+    async fn test_dns_find() {
+        let (config, _) = serve(vec![(
+            200,
+            r#"{"reply":{"code":300,"detail":"success","resource_record":[
+                {"record_id":"a1","type":"A","host":"example.xyz","value":"1.1.1.1","ttl":"3600","distance":0},
+                {"record_id":"b2","type":"AAAA","host":"t.example.xyz","value":"2606:4700::1","ttl":7207}
+            ]}}"#,
+        )])
+        .await;
+        let record = dns_find(&config).await.unwrap().unwrap();
+        assert_eq!(record.record_id, "b2");
+        assert_eq!(record.record_type, "AAAA");
+        assert_eq!(record.ttl, 7207);
+        assert!(record.accepts("2606:4700::2".parse().unwrap()));
+        assert!(!record.accepts("1.1.1.1".parse().unwrap()));
+    }
+
+    /// 测试非5xx的HTTP错误不重试
+    #[tokio::test]
+    async fn test_no_retry_client_error() {
+        let (config, requests) = serve(vec![(404, ""), (200, LIST_OK)]).await;
+        assert!(dns_list(&config).await.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    /// 测试每个配置的请求超时都会生效，不沿用先使用的配置
+    #[tokio::test]
+    async fn test_timeout_per_config() {
+        // 接受连接但不返回响应
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut streams = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+            }
+        });
+
+        let (first, _) = serve(vec![(200, LIST_OK)]).await;
+        dns_list(&NamesiloConfig {
+            timeout_ms: 60_000,
+            ..first
+        })
+        .await
+        .unwrap();
+
+        let config = NamesiloConfig {
+            timeout_ms: 100,
+            retries: 0,
+            ..config(&url)
+        };
+        let started = Instant::now();
+        assert!(dns_list(&config).await.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    /// 测试退避时间翻倍且不超过上限，抖动在上限的一半到上限之间
+    #[test]
+    fn test_backoff() {
+        let config = NamesiloConfig {
+            backoff_ms: 500,
+            max_backoff_ms: 3000,
+            ..config("")
+        };
+        assert_eq!(config.backoff(0), Duration::from_millis(500));
+        assert_eq!(config.backoff(2), Duration::from_millis(2000));
+        assert_eq!(config.backoff(3), Duration::from_millis(3000));
+        assert_eq!(config.backoff(64), Duration::from_millis(3000));
+        for _ in 0..100 {
+            let delay = jitter(Duration::from_millis(1000));
+            assert!(delay >= Duration::from_millis(500) && delay <= Duration::from_millis(1000));
+        }
+    }
 }
@@ -0,0 +1,71 @@
+//! TCP连接探测模块
+//!
+//! 通过多次建立TCP连接测量单个IP的延迟和丢包率，
+//! 用于DNS同步后的可用性验证
+
+use crate::configure::probe::ProbeConfig;
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Instant;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+/// 探测结果
+///
+/// # 字段
+/// - `sent`: 尝试连接次数
+/// - `received`: 连接成功次数
+/// - `loss`: 丢包率(0~1)
+/// - `latency_ms`: 成功连接的平均耗时(毫秒)，全部失败时为空
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeResult {
+    pub sent: u32,
+    pub received: u32,
+    pub loss: f64,
+    pub latency_ms: Option<f64>,
+}
+
+impl ProbeResult {
+    /// 是否所有连接都失败
+    pub fn is_unreachable(&self) -> bool {
+        self.received == 0
+    }
+}
+
+/// 探测指定IP
+///
+/// # 参数
+/// - `config`: 探测配置
+/// - `ip`: 目标IP
+///
+/// # 返回值
+/// 返回探测结果，连接失败计入丢包而不是返回错误
+pub async fn tcp_probe(config: &ProbeConfig, ip: IpAddr) -> ProbeResult {
+    let addr = SocketAddr::new(ip, config.port);
+    let mut received = 0;
+    let mut total_ms = 0.0;
+
+    for _ in 0..config.count {
+        let start = Instant::now();
+        match timeout(config.timeout(), TcpStream::connect(addr)).await {
+            Ok(Ok(_)) => {
+                received += 1;
+                total_ms += start.elapsed().as_secs_f64() * 1000.0;
+            }
+            Ok(Err(e)) => tracing::debug!("probe {} failed: {}", addr, e),
+            Err(_) => tracing::debug!("probe {} timed out", addr),
+        }
+    }
+
+    let sent = config.count;
+    ProbeResult {
+        sent,
+        received,
+        loss: if sent == 0 {
+            0.0
+        } else {
+            f64::from(sent - received) / f64::from(sent)
+        },
+        latency_ms: (received > 0).then(|| total_ms / f64::from(received)),
+    }
+}
@@ -0,0 +1,108 @@
+//! DNS服务商模块
+//!
+//! 按`dns.provider`配置选择DNS服务商(Namesilo、RFC 2136或Webhook)，
+//! 为同步、回滚、监控和故障切换提供统一的：
+//! - 目标记录主机名和TTL
+//! - 目标记录查找(记录不存在时可创建的服务商返回空记录)
+//! - 目标记录更新
+//! - 服务商错误到响应码的转换
+
+use crate::client::namesilo::{NamesiloError, ResourceRecord};
+use crate::client::rfc2136::Rfc2136Error;
+use crate::client::webhook::WebhookError;
+use crate::client::{namesilo, rfc2136, webhook};
+use crate::configure::AppConfig;
+use crate::configure::dns::Provider;
+use crate::model::response::Code;
+use std::net::IpAddr;
+
+/// 目标记录的完整主机名
+pub fn target_host(config: &AppConfig) -> String {
+    match config.dns.provider {
+        Provider::Namesilo => config.namesilo.target_host(),
+        Provider::Rfc2136 => config.rfc2136.target_host(),
+        Provider::Webhook => config.webhook.target_host(),
+    }
+}
+
+/// 同步和故障切换时更新后的记录TTL
+pub fn ttl(config: &AppConfig) -> u32 {
+    match config.dns.provider {
+        Provider::Namesilo => config.namesilo.ttl(),
+        Provider::Rfc2136 => config.rfc2136.ttl,
+        Provider::Webhook => config.webhook.ttl,
+    }
+}
+
+/// 查找目标记录
+///
+/// # 参数
+/// - `config`: 应用配置
+///
+/// # 返回值
+/// - 成功: 返回目标记录，不存在时返回None
+/// - 失败: 调用DNS服务商失败
+pub async fn find(config: &AppConfig) -> anyhow::Result<Option<ResourceRecord>> {
+    match config.dns.provider {
+        Provider::Namesilo => namesilo::dns_find(&config.namesilo).await,
+        Provider::Rfc2136 => rfc2136::find(&config.rfc2136).await,
+        Provider::Webhook => webhook::find(&config.webhook).await,
+    }
+}
+
+/// 目标记录不存在时用于创建记录的空记录
+///
+/// # 返回值
+/// 更新时可以创建记录的服务商(RFC 2136)返回值为空的记录，其余返回None
+pub fn missing_record(config: &AppConfig) -> Option<ResourceRecord> {
+    match config.dns.provider {
+        Provider::Rfc2136 => Some(ResourceRecord {
+            record_id: String::new(),
+            record_type: config.rfc2136.record_type.clone(),
+            host: config.rfc2136.target_host(),
+            value: String::new(),
+            ttl: 0,
+        }),
+        Provider::Namesilo | Provider::Webhook => None,
+    }
+}
+
+/// 将目标记录更新为指定IP
+///
+/// # 参数
+/// - `config`: 应用配置
+/// - `record`: 目标记录(更新前，RFC 2136可以是`missing_record`返回的空记录)
+/// - `ip`: 新的IP
+/// - `ttl`: 记录TTL
+///
+/// # 返回值
+/// - 成功: Ok(())
+/// - 失败: 调用DNS服务商失败
+pub async fn update(
+    config: &AppConfig,
+    record: &ResourceRecord,
+    ip: IpAddr,
+    ttl: u32,
+) -> anyhow::Result<()> {
+    match config.dns.provider {
+        Provider::Namesilo => {
+            namesilo::dns_update(&config.namesilo, &ip.to_string(), &record.record_id, ttl).await
+        }
+        Provider::Rfc2136 => rfc2136::update(&config.rfc2136, ip, ttl).await,
+        Provider::Webhook => webhook::update(&config.webhook, ip, ttl).await,
+    }
+}
+
+/// DNS服务商拒绝请求时对应的响应码
+///
+/// # 返回值
+/// 服务商业务错误返回对应的响应码，其余错误返回None
+pub fn error_code(err: &anyhow::Error) -> Option<Code> {
+    if let Some(err) = err.downcast_ref::<NamesiloError>() {
+        return Some(err.code());
+    }
+    if let Some(err) = err.downcast_ref::<Rfc2136Error>() {
+        return Some(err.code());
+    }
+    err.downcast_ref::<WebhookError>().map(WebhookError::code)
+}
@@ -0,0 +1,340 @@
+//! RFC 2136动态更新客户端模块
+//!
+//! 通过TSIG签名的UPDATE报文修改自建权威服务器(BIND、Knot等)上的记录：
+//! - 查询目标记录的当前值和TTL
+//! - 在一个UPDATE报文中删除目标记录集并添加新值，由服务器原子地执行(记录不存在时即为创建)
+//! - 校验响应的TSIG签名，服务器拒绝时返回对应的响应码
+
+use crate::client::dns;
+use crate::client::namesilo::ResourceRecord;
+use crate::configure::propagation::Transport;
+use crate::configure::rfc2136::{Rfc2136Config, TsigAlgorithm};
+use crate::model::response::Code;
+use anyhow::{Context, anyhow, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use hickory_proto::dnssec::rdata::tsig;
+use hickory_proto::dnssec::tsig::TSigner;
+use hickory_proto::op::{Message, MessageType, OpCode, Query, ResponseCode, UpdateMessage};
+use hickory_proto::rr::rdata::{A, AAAA};
+use hickory_proto::rr::{DNSClass, Name, RData, Record, RecordType};
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+/// 允许的客户端与服务器时间差(秒)
+const TSIG_FUDGE: u16 = 300;
+
+/// 查找目标记录
+///
+/// # 参数
+/// - `config`: RFC 2136配置
+///
+/// # 返回值
+/// - 成功: 返回目标记录，不存在时返回None
+/// - 失败: 配置错误或查询失败
+pub async fn find(config: &Rfc2136Config) -> anyhow::Result<Option<ResourceRecord>> {
+    let server = config.server()?;
+    let answers = dns::query(
+        server,
+        &record_name(config)?,
+        RecordType::from_str(&config.record_type)?,
+        Transport::Tcp,
+        config.timeout(),
+        false,
+    )
+    .await
+    .map_err(|e| anyhow!("query {} on {} failed: {}", config.target_host(), server, e))?;
+
+    Ok(answers.into_iter().next().map(|answer| ResourceRecord {
+        record_id: String::new(),
+        record_type: config.record_type.clone(),
+        host: config.target_host(),
+        value: answer.value,
+        ttl: answer.ttl,
+    }))
+}
+
+/// 将目标记录更新为指定IP
+///
+/// # 参数
+/// - `config`: RFC 2136配置
+/// - `ip`: 新的IP
+/// - `ttl`: 记录TTL
+///
+/// # 返回值
+/// - 成功: Ok(())
+/// - 失败: 配置错误、网络错误、服务器拒绝或响应签名无效
+pub async fn update(config: &Rfc2136Config, ip: IpAddr, ttl: u32) -> anyhow::Result<()> {
+    let server = config.server()?;
+    let name = record_name(config)?;
+    let rdata = match ip {
+        IpAddr::V4(ip) => RData::A(A(ip)),
+        IpAddr::V6(ip) => RData::AAAA(AAAA(ip)),
+    };
+
+    let mut message = Message::new();
+    message
+        .set_id(dns::message_id())
+        .set_message_type(MessageType::Query)
+        .set_op_code(OpCode::Update);
+    message.add_zone(Query::query(zone_name(config)?, RecordType::SOA));
+    // 删除整个记录集后添加新值，保证更新后只有一个值
+    let mut delete = Record::update0(name.clone(), 0, rdata.record_type());
+    delete.set_dns_class(DNSClass::ANY);
+    message.add_update(delete);
+    message.add_update(Record::from_rdata(name, ttl, rdata));
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let mut verifier = message
+        .finalize(&signer(config)?, now as u32)?
+        .ok_or_else(|| anyhow!("tsig signer returned no verifier"))?;
+    let request = message.to_vec()?;
+
+    let response = tokio::time::timeout(config.timeout(), dns::exchange_tcp(server, &request))
+        .await
+        .map_err(|_| anyhow!("rfc2136 update to {} timed out", server))?
+        .map_err(|e| anyhow!("rfc2136 update to {} failed: {}", server, e))?;
+    let reply = Message::from_vec(&response)?;
+    if reply.id() != message.id() {
+        bail!("response id from {} does not match the update", server);
+    }
+    if reply.response_code() != ResponseCode::NoError {
+        return Err(Rfc2136Error {
+            host: config.target_host(),
+            code: reply.response_code(),
+        }
+        .into());
+    }
+    verifier(&response).map_err(|e| anyhow!("invalid tsig in response from {}: {}", server, e))?;
+
+    info!(
+        "rfc2136 update of {} accepted by {}",
+        config.target_host(),
+        server
+    );
+    Ok(())
+}
+
+/// 根据配置创建TSIG签名器
+fn signer(config: &Rfc2136Config) -> anyhow::Result<TSigner> {
+    let key = STANDARD
+        .decode(config.secret.trim())
+        .context("invalid tsig secret, expected base64")?;
+    let algorithm = match config.algorithm {
+        TsigAlgorithm::HmacSha256 => tsig::TsigAlgorithm::HmacSha256,
+        TsigAlgorithm::HmacSha384 => tsig::TsigAlgorithm::HmacSha384,
+        TsigAlgorithm::HmacSha512 => tsig::TsigAlgorithm::HmacSha512,
+    };
+    let name = Name::from_ascii(&config.key_name)?;
+
+    Ok(TSigner::new(key, algorithm, name, TSIG_FUDGE)?)
+}
+
+/// 区域名(绝对域名)
+fn zone_name(config: &Rfc2136Config) -> anyhow::Result<Name> {
+    let mut name = Name::from_ascii(&config.zone)?;
+    name.set_fqdn(true);
+    Ok(name)
+}
+
+/// 目标记录名(绝对域名)
+fn record_name(config: &Rfc2136Config) -> anyhow::Result<Name> {
+    let mut name = Name::from_ascii(config.target_host())?;
+    name.set_fqdn(true);
+    Ok(name)
+}
+
+/// 服务器拒绝更新
+///
+/// # 字段
+/// - `host`: 目标记录
+/// - `code`: 服务器返回的响应码
+#[derive(Debug)]
+pub struct Rfc2136Error {
+    pub host: String,
+    pub code: ResponseCode,
+}
+
+impl Rfc2136Error {
+    /// 对应的API响应状态码
+    pub fn code(&self) -> Code {
+        match self.code {
+            // TSIG密钥或签名无效
+            ResponseCode::NotAuth
+            | ResponseCode::BADSIG
+            | ResponseCode::BADKEY
+            | ResponseCode::BADTIME => Code::Unauthorized,
+            ResponseCode::Refused => Code::Forbidden,
+            ResponseCode::NotZone | ResponseCode::FormErr => Code::InvalidParameter,
+            _ => Code::BadGateway,
+        }
+    }
+}
+
+impl Display for Rfc2136Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rfc2136 update of {} rejected: {}", self.host, self.code)
+    }
+}
+
+impl std::error::Error for Rfc2136Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hickory_proto::dnssec::rdata::tsig::TSIG;
+    use std::net::{Ipv4Addr, SocketAddr};
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const SECRET: &str = "c2VjcmV0LWtleS1mb3ItdGVzdHMtb25seQ==";
+
+    type Zone = Arc<Mutex<Option<(Ipv4Addr, u32)>>>;
+
+    /// 处理一个请求，返回响应报文
+    ///
+    /// 查询直接返回当前记录；UPDATE校验TSIG签名后执行并对响应签名，签名无效时返回NOTAUTH
+    fn handle(request: &[u8], signer: &TSigner, zone: &Zone) -> Vec<u8> {
+        let message = Message::from_vec(request).unwrap();
+        let mut response = Message::new();
+        response
+            .set_id(message.id())
+            .set_message_type(MessageType::Response)
+            .set_op_code(message.op_code())
+            .add_queries(message.queries().to_vec());
+
+        if message.op_code() == OpCode::Query {
+            if let Some((ip, ttl)) = *zone.lock().unwrap() {
+                let name = message.queries()[0].name().clone();
+                response.add_answer(Record::from_rdata(name, ttl, RData::A(A(ip))));
+            }
+            return response.to_vec().unwrap();
+        }
+
+        let Ok((mac, _, time)) = signer.verify_message_byte(None, request, true) else {
+            response.set_response_code(ResponseCode::NotAuth);
+            return response.to_vec().unwrap();
+        };
+        for record in message.updates() {
+            match (record.dns_class(), record.data()) {
+                (DNSClass::ANY, _) => *zone.lock().unwrap() = None,
+                (DNSClass::IN, RData::A(a)) => *zone.lock().unwrap() = Some((a.0, record.ttl())),
+                _ => {}
+            }
+        }
+
+        let pre_tsig = TSIG::new(
+            signer.algorithm().clone(),
+            time,
+            signer.fudge(),
+            Vec::new(),
+            response.id(),
+            0,
+            Vec::new(),
+        );
+        let tbs =
+            tsig::message_tbs(Some(&mac), &response, &pre_tsig, signer.signer_name()).unwrap();
+        let mac = signer.sign(&tbs).unwrap();
+        response.add_tsig(tsig::make_tsig_record(
+            signer.signer_name().clone(),
+            pre_tsig.set_mac(mac),
+        ));
+        response.to_vec().unwrap()
+    }
+
+    /// 启动本地权威服务器(只支持TCP)
+    async fn serve(zone: Zone) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let signer = signer(&config(addr, SECRET)).unwrap();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let len = stream.read_u16().await.unwrap();
+                let mut buf = vec![0; usize::from(len)];
+                stream.read_exact(&mut buf).await.unwrap();
+                let response = handle(&buf, &signer, &zone);
+                stream.write_u16(response.len() as u16).await.unwrap();
+                stream.write_all(&response).await.unwrap();
+            }
+        });
+
+        addr
+    }
+
+    fn config(server: SocketAddr, secret: &str) -> Rfc2136Config {
+        Rfc2136Config {
+            server: server.to_string(),
+            zone: "example.xyz".to_string(),
+            host: "t".to_string(),
+            record_type: "A".to_string(),
+            ttl: 60,
+            key_name: "cfselect".to_string(),
+            algorithm: TsigAlgorithm::HmacSha256,
+            secret: secret.to_string(),
+            timeout_ms: 1000,
+        }
+    }
+
+    /// 测试查询和签名更新记录
+    #[tokio::test]
+    async fn test_update() {
+        let zone: Zone = Arc::new(Mutex::new(Some((Ipv4Addr::new(104, 16, 1, 1), 300))));
+        let config = config(serve(zone.clone()).await, SECRET);
+
+        let record = find(&config).await.unwrap().unwrap();
+        assert_eq!(record.host, "t.example.xyz");
+        assert_eq!(record.value, "104.16.1.1");
+        assert_eq!(record.ttl, 300);
+
+        update(&config, "104.16.1.2".parse().unwrap(), config.ttl)
+            .await
+            .unwrap();
+        assert_eq!(
+            *zone.lock().unwrap(),
+            Some((Ipv4Addr::new(104, 16, 1, 2), 60))
+        );
+
+        let record = find(&config).await.unwrap().unwrap();
+        assert_eq!(record.value, "104.16.1.2");
+        assert_eq!(record.ttl, 60);
+    }
+
+    /// 测试记录不存在时通过更新创建记录
+    #[tokio::test]
+    async fn test_create() {
+        let zone: Zone = Arc::new(Mutex::new(None));
+        let config = config(serve(zone.clone()).await, SECRET);
+        assert!(find(&config).await.unwrap().is_none());
+
+        update(&config, "104.16.1.2".parse().unwrap(), config.ttl)
+            .await
+            .unwrap();
+        let record = find(&config).await.unwrap().unwrap();
+        assert_eq!(record.value, "104.16.1.2");
+        assert_eq!(record.ttl, 60);
+    }
+
+    /// 测试密钥错误时服务器拒绝更新
+    #[tokio::test]
+    async fn test_bad_key() {
+        let zone: Zone = Arc::new(Mutex::new(Some((Ipv4Addr::new(104, 16, 1, 1), 300))));
+        let config = config(serve(zone.clone()).await, "d3Jvbmcta2V5");
+
+        let err = update(&config, "104.16.1.2".parse().unwrap(), config.ttl)
+            .await
+            .unwrap_err();
+        let err = err.downcast_ref::<Rfc2136Error>().unwrap();
+        assert_eq!(err.code, ResponseCode::NotAuth);
+        assert_eq!(err.code(), Code::Unauthorized);
+        assert_eq!(
+            *zone.lock().unwrap(),
+            Some((Ipv4Addr::new(104, 16, 1, 1), 300))
+        );
+    }
+}
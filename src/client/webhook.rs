@@ -0,0 +1,368 @@
+//! Webhook DNS服务商客户端模块
+//!
+//! 对于未内置支持的DNS服务商，通过调用自定义HTTP接口修改记录：
+//! - 按模板生成JSON请求体(IP、主机名、域名、TTL、记录类型)
+//! - 附加配置的请求头，可选地对请求体进行HMAC-SHA256签名
+//! - 将接口返回的HTTP状态转换为对应的响应码
+//! - 通过查询权威DNS服务器获取记录当前值
+
+use crate::client::dns;
+use crate::client::namesilo::ResourceRecord;
+use crate::configure::propagation::Transport;
+use crate::configure::webhook::WebhookConfig;
+use crate::model::response::Code;
+use anyhow::{Context, anyhow};
+use hickory_proto::rr::{Name, RecordType};
+use hmac::{Hmac, Mac};
+use reqwest::StatusCode;
+use reqwest::header::CONTENT_TYPE;
+use sha2::Sha256;
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::LazyLock;
+use tracing::info;
+
+// 全局HTTP客户端，使用LazyLock确保线程安全初始化，超时时间按请求设置
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .build()
+        .expect("Failed to create reqwest client")
+});
+
+/// 错误信息中保留的接口响应内容长度(字符)
+const MAX_DETAIL_CHARS: usize = 200;
+
+/// 查找目标记录
+///
+/// 接口无法返回记录当前值，通过向配置的权威DNS服务器查询获取。查询不请求递归，
+/// 并要求权威应答，避免使用递归解析器缓存的旧值
+///
+/// # 参数
+/// - `config`: Webhook配置
+///
+/// # 返回值
+/// - 成功: 返回目标记录，不存在时返回None
+/// - 失败: 配置错误或查询失败
+pub async fn find(config: &WebhookConfig) -> anyhow::Result<Option<ResourceRecord>> {
+    let server = config.nameserver()?;
+    let mut name = Name::from_ascii(config.target_host())?;
+    name.set_fqdn(true);
+    let answers = dns::query(
+        server,
+        &name,
+        RecordType::from_str(&config.record_type)?,
+        Transport::Udp,
+        config.timeout(),
+        true,
+    )
+    .await
+    .map_err(|e| anyhow!("query {} on {} failed: {}", config.target_host(), server, e))?;
+
+    Ok(answers.into_iter().next().map(|answer| ResourceRecord {
+        record_id: String::new(),
+        record_type: config.record_type.clone(),
+        host: config.target_host(),
+        value: answer.value,
+        ttl: answer.ttl,
+    }))
+}
+
+/// 调用接口将目标记录更新为指定IP
+///
+/// # 参数
+/// - `config`: Webhook配置
+/// - `ip`: 新的IP
+/// - `ttl`: 记录TTL
+///
+/// # 返回值
+/// - 成功: 接口返回2xx
+/// - 失败: 模板错误、网络错误或接口返回非2xx状态
+pub async fn update(config: &WebhookConfig, ip: IpAddr, ttl: u32) -> anyhow::Result<()> {
+    let body = render(config, ip, ttl)?;
+
+    let mut request = CLIENT
+        .post(&config.url)
+        .timeout(config.timeout())
+        .header(CONTENT_TYPE, mime::APPLICATION_JSON.as_ref());
+    for (name, value) in &config.headers {
+        request = request.header(name, value);
+    }
+    if !config.secret.is_empty() {
+        request = request.header(&config.signature_header, sign(&config.secret, &body));
+    }
+
+    // 去掉错误信息中的URL，避免泄露URL中的令牌
+    let response = request
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| anyhow!("webhook request failed: {}", e.without_url()))?;
+    let status = response.status();
+    let detail = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(WebhookError {
+            status,
+            detail: detail.chars().take(MAX_DETAIL_CHARS).collect(),
+        }
+        .into());
+    }
+
+    info!(
+        "webhook update of {} to {} accepted with {}",
+        config.target_host(),
+        ip,
+        status
+    );
+    Ok(())
+}
+
+/// 按模板生成请求体
+///
+/// 占位符替换为转义后的值，因此字符串类型的值需要在模板中加引号
+///
+/// # 返回值
+/// - 成功: 返回请求体
+/// - 失败: 生成的请求体不是合法的JSON
+fn render(config: &WebhookConfig, ip: IpAddr, ttl: u32) -> anyhow::Result<String> {
+    let host = match config.host.trim_end_matches('.') {
+        "" => "@",
+        host => host,
+    };
+    let body = [
+        ("{{ip}}", ip.to_string()),
+        ("{{host}}", host.to_string()),
+        (
+            "{{domain}}",
+            config.domain.trim_end_matches('.').to_string(),
+        ),
+        ("{{ttl}}", ttl.to_string()),
+        ("{{record_type}}", config.record_type.clone()),
+    ]
+    .iter()
+    .fold(config.body.clone(), |body, (placeholder, value)| {
+        body.replace(placeholder, &escape(value))
+    });
+
+    serde_json::from_str::<serde_json::Value>(&body)
+        .with_context(|| format!("webhook body is not valid json: {}", body))?;
+    Ok(body)
+}
+
+/// 按JSON字符串规则转义(不含两侧引号)
+fn escape(value: &str) -> String {
+    let quoted = serde_json::Value::from(value).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// 计算请求体的HMAC-SHA256签名
+///
+/// # 返回值
+/// `sha256=<十六进制签名>`
+fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body.as_bytes());
+    let signature: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("sha256={}", signature)
+}
+
+/// 接口返回了非2xx状态
+///
+/// # 字段
+/// - `status`: HTTP状态
+/// - `detail`: 响应内容(截断)
+#[derive(Debug)]
+pub struct WebhookError {
+    pub status: StatusCode,
+    pub detail: String,
+}
+
+impl WebhookError {
+    /// 对应的API响应状态码
+    pub fn code(&self) -> Code {
+        match self.status {
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => Code::InvalidParameter,
+            StatusCode::UNAUTHORIZED => Code::Unauthorized,
+            StatusCode::FORBIDDEN => Code::Forbidden,
+            StatusCode::NOT_FOUND => Code::NotFound,
+            StatusCode::CONFLICT => Code::Conflict,
+            StatusCode::TOO_MANY_REQUESTS => Code::TooManyRequests,
+            _ => Code::BadGateway,
+        }
+    }
+}
+
+impl Display for WebhookError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "webhook returned {}: {}", self.status, self.detail)
+    }
+}
+
+impl std::error::Error for WebhookError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    type Captured = Arc<Mutex<Vec<String>>>;
+
+    /// 启动本地HTTP服务，记录收到的请求并返回指定的响应
+    async fn serve(status: u16, body: &'static str) -> (WebhookConfig, Captured) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let captured = Captured::default();
+        let requests = captured.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                // 读取完整的请求头和请求体
+                while !is_complete(&request) {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                requests
+                    .lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&request).to_string());
+                let response = format!(
+                    "HTTP/1.1 {} X\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (config(&format!("http://{}/dns", addr)), captured)
+    }
+
+    /// 请求是否已按content-length读取完整
+    fn is_complete(request: &[u8]) -> bool {
+        let text = String::from_utf8_lossy(request);
+        let Some((head, body)) = text.split_once("\r\n\r\n") else {
+            return false;
+        };
+        let length = head
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length: "))
+            .and_then(|length| length.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        body.len() >= length
+    }
+
+    fn config(url: &str) -> WebhookConfig {
+        WebhookConfig {
+            url: url.to_string(),
+            body: r#"{"ip": "{{ip}}", "name": "{{host}}.{{domain}}", "ttl": {{ttl}}, "type": "{{record_type}}"}"#
+                .to_string(),
+            headers: BTreeMap::from([("authorization".to_string(), "Bearer token".to_string())]),
+            secret: "secret".to_string(),
+            signature_header: "X-Signature-256".to_string(),
+            domain: "example.xyz".to_string(),
+            host: "t".to_string(),
+            record_type: "A".to_string(),
+            ttl: 300,
+            nameserver: "127.0.0.1".to_string(),
+            timeout_ms: 1000,
+        }
+    }
+
+    /// 测试请求体模板的替换、转义及JSON校验
+    #[test]
+    fn test_render() {
+        let mut config = config("http://127.0.0.1/dns");
+        let body = render(&config, "104.16.1.1".parse().unwrap(), 300).unwrap();
+        assert_eq!(
+            body,
+            r#"{"ip": "104.16.1.1", "name": "t.example.xyz", "ttl": 300, "type": "A"}"#
+        );
+
+        config.host = r#"a"b"#.to_string();
+        let body = render(&config, "104.16.1.1".parse().unwrap(), 300).unwrap();
+        assert!(body.contains(r#""name": "a\"b.example.xyz""#));
+
+        config.body = r#"{"ip": {{ip}}}"#.to_string();
+        assert!(render(&config, "104.16.1.1".parse().unwrap(), 300).is_err());
+    }
+
+    /// 测试HMAC-SHA256签名(RFC 4231测试用例2)
+    #[test]
+    fn test_sign() {
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    /// 测试请求包含模板生成的请求体、附加请求头和签名
+    #[tokio::test]
+    async fn test_update() {
+        let (config, captured) = serve(200, "ok").await;
+        update(&config, "104.16.1.2".parse().unwrap(), 300)
+            .await
+            .unwrap();
+
+        let request = captured.lock().unwrap()[0].clone();
+        let body = r#"{"ip": "104.16.1.2", "name": "t.example.xyz", "ttl": 300, "type": "A"}"#;
+        assert!(request.starts_with("POST /dns "));
+        assert!(request.contains("authorization: Bearer token\r\n"));
+        assert!(request.contains(&format!("x-signature-256: {}\r\n", sign("secret", body))));
+        assert!(request.ends_with(body));
+    }
+
+    /// 测试接口返回的HTTP状态转换为响应码，并保留响应内容
+    #[tokio::test]
+    async fn test_remote_status() {
+        let (config, _) = serve(403, "ip not allowed").await;
+        let err = update(&config, "104.16.1.2".parse().unwrap(), 300)
+            .await
+            .unwrap_err();
+        let err = err.downcast_ref::<WebhookError>().unwrap();
+        assert_eq!(err.code(), Code::Forbidden);
+        assert_eq!(
+            err.to_string(),
+            "webhook returned 403 Forbidden: ip not allowed"
+        );
+    }
+
+    /// 测试未配置权威DNS服务器时无法查询记录当前值
+    #[tokio::test]
+    async fn test_find_requires_nameserver() {
+        let mut config = config("http://127.0.0.1/dns");
+        config.nameserver = String::new();
+        let err = find(&config).await.unwrap_err();
+        assert!(err.to_string().starts_with("webhook.nameserver is not set"));
+    }
+
+    /// 测试网络错误信息中不包含URL(及其中的令牌)
+    #[tokio::test]
+    async fn test_error_hides_url() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let config = config(&format!("http://{}/dns?token=secret-token", addr));
+        let err = update(&config, "104.16.1.2".parse().unwrap(), 300)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("webhook request failed"));
+        assert!(!err.contains("secret-token"));
+    }
+}
@@ -0,0 +1,29 @@
+//! IP段目录配置模块
+//!
+//! 定义内置Cloudflare IP段的刷新来源
+
+use serde::Deserialize;
+
+/// Cloudflare IP段目录配置
+///
+/// # 字段
+/// - `urls`: 在线刷新地址列表(每个地址返回每行一个IP段的纯文本)
+/// - `file`: 离线刷新使用的本地文件路径(为空表示未配置)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct CatalogConfig {
+    pub urls: Vec<String>,
+    pub file: String,
+}
+
+impl Default for CatalogConfig {
+    fn default() -> Self {
+        Self {
+            urls: vec![
+                "https://www.cloudflare.com/ips-v4".to_string(),
+                "https://www.cloudflare.com/ips-v6".to_string(),
+            ],
+            file: String::new(),
+        }
+    }
+}
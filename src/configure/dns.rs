@@ -0,0 +1,28 @@
+//! DNS服务商选择配置模块
+//!
+//! 定义同步、回滚、监控和故障切换使用的DNS服务商
+
+use serde::Deserialize;
+
+/// DNS服务商选择配置
+///
+/// # 字段
+/// - `provider`: 使用的DNS服务商，对应的配置在同名配置段中
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct DnsConfig {
+    pub provider: Provider,
+}
+
+/// DNS服务商
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Provider {
+    /// Namesilo API
+    #[default]
+    Namesilo,
+    /// RFC 2136动态更新(自建BIND、Knot等权威服务器)
+    Rfc2136,
+    /// 调用自定义HTTP接口
+    Webhook,
+}
@@ -0,0 +1,26 @@
+//! 排除列表配置模块
+//!
+//! 定义DNS同步后自动拉黑验证失败IP的相关配置
+
+use serde::Deserialize;
+
+/// 排除列表配置
+///
+/// # 字段
+/// - `auto_block`: 同步DNS后验证IP，验证失败时自动加入排除列表
+/// - `block_ttl_secs`: 自动拉黑的有效期(秒)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ExclusionConfig {
+    pub auto_block: bool,
+    pub block_ttl_secs: i64,
+}
+
+impl Default for ExclusionConfig {
+    fn default() -> Self {
+        Self {
+            auto_block: true,
+            block_ttl_secs: 86400,
+        }
+    }
+}
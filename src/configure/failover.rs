@@ -0,0 +1,100 @@
+//! 故障切换配置模块
+//!
+//! 定义已发布IP劣化时自动切换到候选IP的策略
+
+use anyhow::{Context, ensure};
+use chrono::{Duration, Local};
+use serde::Deserialize;
+
+/// 故障切换配置
+///
+/// # 字段
+/// - `enabled`: 是否启用自动切换(依赖后台监控)
+/// - `max_latency_ms`: 延迟阈值(毫秒)，超过视为劣化
+/// - `max_loss`: 丢包率阈值(0~1)，超过视为劣化
+/// - `consecutive`: 连续劣化多少次后触发切换
+/// - `cooldown_secs`: 两次切换之间的最短间隔(秒，必须为正数)
+/// - `candidates`: 最多尝试的候选IP数量
+/// - `dry_run`: 只记录切换计划，不修改DNS记录
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct FailoverConfig {
+    pub enabled: bool,
+    pub max_latency_ms: f64,
+    pub max_loss: f64,
+    pub consecutive: u32,
+    pub cooldown_secs: i64,
+    pub candidates: usize,
+    pub dry_run: bool,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_latency_ms: 300.0,
+            max_loss: 0.5,
+            consecutive: 3,
+            cooldown_secs: 1800,
+            candidates: 5,
+            dry_run: false,
+        }
+    }
+}
+
+impl FailoverConfig {
+    /// 校验配置项
+    ///
+    /// 冷却时间必须为正数且不超出时间范围，否则冷却期失效或计算时溢出
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.cooldown_secs > 0,
+            "failover.cooldown_secs must be positive"
+        );
+        Duration::try_seconds(self.cooldown_secs)
+            .and_then(|cooldown| Local::now().checked_sub_signed(cooldown))
+            .context("failover.cooldown_secs is out of range")?;
+        Ok(())
+    }
+
+    /// 获取冷却时长
+    ///
+    /// 加载配置时已校验，超出范围时按最大时长处理
+    pub fn cooldown(&self) -> Duration {
+        Duration::try_seconds(self.cooldown_secs).unwrap_or(Duration::MAX)
+    }
+
+    /// 判断探测结果是否超过阈值
+    ///
+    /// 全部连接失败时没有延迟数据，同样视为劣化
+    pub fn is_degraded(&self, loss: f64, latency_ms: Option<f64>) -> bool {
+        loss > self.max_loss || latency_ms.is_none_or(|latency| latency > self.max_latency_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试延迟或丢包率超过阈值、全部连接失败时视为劣化
+    #[test]
+    fn test_is_degraded() {
+        let config = FailoverConfig::default();
+        assert!(!config.is_degraded(0.0, Some(120.0)));
+        assert!(!config.is_degraded(0.5, Some(300.0)));
+        assert!(config.is_degraded(0.0, Some(300.1)));
+        assert!(config.is_degraded(0.75, Some(120.0)));
+        assert!(config.is_degraded(1.0, None));
+    }
+
+    /// 测试冷却时间必须为正数且不超出时间范围
+    #[test]
+    fn test_validate() {
+        let mut config = FailoverConfig::default();
+        assert!(config.validate().is_ok());
+        for secs in [0, -1, i64::MAX] {
+            config.cooldown_secs = secs;
+            assert!(config.validate().is_err());
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! IP历史配置模块
+//!
+//! 定义单个IP历史测量数据的保留、降采样、多次任务排名及DNS变更记录参数
+
+use anyhow::{Context, ensure};
+use chrono::{Duration, Local};
+use serde::Deserialize;
+
+/// IP历史配置
+///
+/// # 字段
+/// - `retention_days`: 测量数据保留天数(必须为正数)，过期数据在整理时删除
+/// - `max_points`: 查询未指定聚合粒度时返回的最大点数，超出时自动降采样
+/// - `ranking_jobs`: 稳定性排名默认统计的最近成功任务数量
+/// - `dns_changes`: 最多保留的DNS变更记录数量
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub retention_days: i64,
+    pub max_points: usize,
+    pub ranking_jobs: usize,
+    pub dns_changes: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: 30,
+            max_points: 500,
+            ranking_jobs: 5,
+            dns_changes: 500,
+        }
+    }
+}
+
+impl HistoryConfig {
+    /// 校验配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.retention_days > 0,
+            "history.retention_days must be positive"
+        );
+        Duration::try_days(self.retention_days)
+            .and_then(|retention| Local::now().checked_sub_signed(retention))
+            .context("history.retention_days is out of range")?;
+        Ok(())
+    }
+
+    /// 获取测量数据保留时长
+    ///
+    /// 加载配置时已校验，超出范围时按最大时长处理
+    pub fn retention(&self) -> Duration {
+        Duration::try_days(self.retention_days).unwrap_or(Duration::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试保留天数必须为正数且不超出时间范围
+    #[test]
+    fn test_validate() {
+        let mut config = HistoryConfig::default();
+        assert!(config.validate().is_ok());
+        for days in [0, -1, i64::MAX] {
+            config.retention_days = days;
+            assert!(config.validate().is_err());
+        }
+    }
+}
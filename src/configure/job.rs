@@ -0,0 +1,91 @@
+//! 优选任务配置模块
+//!
+//! 定义IP优选任务的排队、超时和历史保留参数
+
+use anyhow::ensure;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// 等待队列容量上限
+const MAX_QUEUE_DEPTH: usize = 100;
+
+/// 优选任务配置
+///
+/// # 字段
+/// - `queue_depth`: 等待队列容量(0~100)，队列满时拒绝新的任务
+/// - `timeout_secs`: 单个任务的最长执行时间(秒，必须为正数)，超时后终止
+/// - `history_size`: 保留的已结束任务数量(包括结果文件，必须为正数)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct JobConfig {
+    pub queue_depth: usize,
+    pub timeout_secs: u64,
+    pub history_size: usize,
+}
+
+impl Default for JobConfig {
+    fn default() -> Self {
+        Self {
+            queue_depth: 5,
+            timeout_secs: 1800,
+            history_size: 50,
+        }
+    }
+}
+
+impl JobConfig {
+    /// 校验配置项
+    ///
+    /// 超时时间为0时任务立即被终止，不保留历史时无法读取优选结果
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(self.timeout_secs > 0, "job.timeout_secs must be positive");
+        ensure!(self.history_size > 0, "job.history_size must be positive");
+        ensure!(
+            self.queue_depth <= MAX_QUEUE_DEPTH,
+            "job.queue_depth must not exceed {}",
+            MAX_QUEUE_DEPTH
+        );
+        Ok(())
+    }
+
+    /// 获取任务超时时间
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试超时时间和历史数量必须为正数，队列容量不超过上限
+    #[test]
+    fn test_validate() {
+        let config = JobConfig::default();
+        assert!(config.validate().is_ok());
+        assert!(
+            JobConfig {
+                queue_depth: 0,
+                ..config.clone()
+            }
+            .validate()
+            .is_ok()
+        );
+        for invalid in [
+            JobConfig {
+                timeout_secs: 0,
+                ..config.clone()
+            },
+            JobConfig {
+                history_size: 0,
+                ..config.clone()
+            },
+            JobConfig {
+                queue_depth: MAX_QUEUE_DEPTH + 1,
+                ..config.clone()
+            },
+        ] {
+            assert!(invalid.validate().is_err());
+        }
+    }
+}
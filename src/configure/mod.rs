@@ -3,11 +3,35 @@
 //! 提供配置文件的读取、解析和初始化功能
 //! 包含以下子模块:
 //! - listen: 监听配置
-//! - log: 日志配置 
+//! - log: 日志配置
+//! - dns: DNS服务商选择配置
 //! - namesilo: Namesilo API配置
+//! - rfc2136: RFC 2136动态更新配置
+//! - webhook: Webhook DNS服务商配置
+//! - storage: 存储配置
+//! - catalog: IP段目录配置
+//! - probe: 探测配置
+//! - exclusion: 排除列表配置
+//! - monitor: 监控配置
+//! - failover: 故障切换配置
+//! - job: 优选任务配置
+//! - history: IP历史配置
+//! - propagation: DNS传播验证配置
 
+use crate::configure::catalog::CatalogConfig;
+use crate::configure::dns::DnsConfig;
+use crate::configure::exclusion::ExclusionConfig;
+use crate::configure::failover::FailoverConfig;
+use crate::configure::history::HistoryConfig;
+use crate::configure::job::JobConfig;
 use crate::configure::listen::ListenConfig;
 use crate::configure::log::LogConfig;
+use crate::configure::monitor::MonitorConfig;
+use crate::configure::probe::ProbeConfig;
+use crate::configure::propagation::PropagationConfig;
+use crate::configure::rfc2136::Rfc2136Config;
+use crate::configure::storage::StorageConfig;
+use crate::configure::webhook::WebhookConfig;
 use anyhow::{Context, Ok};
 use config::Environment;
 use namesilo::NamesiloConfig;
@@ -17,19 +41,69 @@ use tracing::{level_filters::LevelFilter, *};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::{Layer, layer::SubscriberExt, util::SubscriberInitExt};
 
+pub mod catalog;
+pub mod dns;
+pub mod exclusion;
+pub mod failover;
+pub mod history;
+pub mod job;
 pub mod listen;
 pub mod log;
+pub mod monitor;
 pub mod namesilo;
+pub mod probe;
+pub mod propagation;
+pub mod rfc2136;
+pub mod storage;
+pub mod webhook;
 
 /// 应用程序配置结构体
+///
+/// 除listen、log、namesilo外的配置段都有默认值，可以在配置文件中省略
 #[derive(Debug, Deserialize, Clone)]
 pub struct AppConfig {
     /// 监听配置
     pub listen: ListenConfig,
     /// 日志配置
     pub log: LogConfig,
+    /// DNS服务商选择配置
+    #[serde(default)]
+    pub dns: DnsConfig,
     /// Namesilo API配置
     pub namesilo: NamesiloConfig,
+    /// RFC 2136动态更新配置
+    #[serde(default)]
+    pub rfc2136: Rfc2136Config,
+    /// Webhook DNS服务商配置
+    #[serde(default)]
+    pub webhook: WebhookConfig,
+    /// 存储配置
+    #[serde(default)]
+    pub storage: StorageConfig,
+    /// IP段目录配置
+    #[serde(default)]
+    pub catalog: CatalogConfig,
+    /// 探测配置
+    #[serde(default)]
+    pub probe: ProbeConfig,
+    /// 排除列表配置
+    #[serde(default)]
+    pub exclusion: ExclusionConfig,
+    /// 监控配置
+    #[serde(default)]
+    pub monitor: MonitorConfig,
+    /// 故障切换配置
+    #[serde(default)]
+    pub failover: FailoverConfig,
+    /// 优选任务配置
+    #[serde(default)]
+    pub job: JobConfig,
+    /// IP历史配置
+    #[serde(default)]
+    pub history: HistoryConfig,
+    /// DNS传播验证配置
+    #[serde(default)]
+    pub propagation: PropagationConfig,
 }
 
 impl AppConfig {
@@ -37,7 +111,7 @@ impl AppConfig {
     ///
     /// # 返回值
     /// - 成功: 返回解析后的AppConfig
-    /// - 失败: 返回错误信息
+    /// - 失败: 返回错误信息(包括配置项取值不合法)
     pub fn read() -> anyhow::Result<AppConfig> {
         let conf = config::Config::builder()
             .add_source(config::File::with_name("config.toml"))
@@ -47,7 +121,21 @@ impl AppConfig {
             .context("读取配置文件失败")?;
 
         // 尝试将配置对象反序列化为AppConfig类型
-        conf.try_deserialize().context("解析配置文件失败")
+        let config: AppConfig = conf.try_deserialize().context("解析配置文件失败")?;
+        config.validate().context("配置文件校验失败")?;
+        Ok(config)
+    }
+
+    /// 校验配置项的取值范围
+    ///
+    /// # 返回值
+    /// - 成功: Ok(())
+    /// - 失败: 返回第一个不合法的配置项
+    fn validate(&self) -> anyhow::Result<()> {
+        self.monitor.validate()?;
+        self.failover.validate()?;
+        self.job.validate()?;
+        self.history.validate()
     }
 
     /// 初始化日志追踪系统
@@ -96,3 +184,70 @@ impl AppConfig {
         Ok(guards)
     }
 }
+
+#[cfg(test)]
+impl AppConfig {
+    /// 测试用配置
+    ///
+    /// 在只包含必需配置段的配置上叠加`toml`中的配置，数据目录为独立的临时目录
+    pub fn test(toml: &str) -> AppConfig {
+        static SEQ: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let seq = SEQ.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let dir =
+            std::env::temp_dir().join(format!("cfselect-test-{}-{}", std::process::id(), seq));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut config: AppConfig = config::Config::builder()
+            .add_source(config::File::from_str(
+                tests::REQUIRED,
+                config::FileFormat::Toml,
+            ))
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()
+            .and_then(config::Config::try_deserialize)
+            .expect("invalid test config");
+        config.storage.dir = dir.to_string_lossy().into_owned();
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::{File, FileFormat};
+
+    /// 只包含原有(必需)配置段的配置
+    pub const REQUIRED: &str = r#"
+        [listen]
+        host = "0.0.0.0"
+        port = 8989
+
+        [log]
+        file = { enabled = true, level = "info", dir = "logs", name_prefix = "app.log" }
+        console = { enabled = true, level = "trace" }
+
+        [namesilo]
+        url = "https://www.namesilo.com/api"
+        key = ""
+        domain = "example.xyz"
+        rrhost = "t"
+        rrttl = 7207
+    "#;
+
+    /// 测试只包含原有配置段的配置文件可以解析，新增配置段使用默认值
+    #[test]
+    fn test_defaults() {
+        let config: AppConfig = config::Config::builder()
+            .add_source(File::from_str(REQUIRED, FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+
+        assert_eq!(config.dns.provider, dns::Provider::Namesilo);
+        assert_eq!(config.namesilo.retries, 3);
+        assert_eq!(config.storage.dir, "data");
+        assert_eq!(config.history.retention_days, 30);
+        assert!(!config.failover.enabled);
+    }
+}
@@ -0,0 +1,47 @@
+//! 监控配置模块
+//!
+//! 定义已发布DNS记录的后台健康监控参数
+
+use anyhow::ensure;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// DNS记录监控配置
+///
+/// # 字段
+/// - `enabled`: 是否启用后台监控
+/// - `interval_secs`: 检查间隔(秒)，必须为正数
+/// - `history_size`: 保留的探测历史条数
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct MonitorConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    pub history_size: usize,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 300,
+            history_size: 288,
+        }
+    }
+}
+
+impl MonitorConfig {
+    /// 校验配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.interval_secs > 0,
+            "monitor.interval_secs must be positive"
+        );
+        Ok(())
+    }
+
+    /// 获取检查间隔
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+}
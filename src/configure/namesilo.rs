@@ -3,6 +3,17 @@
 //! 定义与Namesilo DNS服务交互所需的配置项
 
 use serde::Deserialize;
+use std::time::Duration;
+
+/// 未配置TTL时Namesilo使用的默认值
+const DEFAULT_TTL: u32 = 7207;
+
+/// 未配置时的默认超时和重试参数
+const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 5000;
+const DEFAULT_TIMEOUT_MS: u64 = 15000;
+const DEFAULT_RETRIES: u32 = 3;
+const DEFAULT_BACKOFF_MS: u64 = 500;
+const DEFAULT_MAX_BACKOFF_MS: u64 = 10000;
 
 /// Namesilo API配置
 ///
@@ -12,6 +23,11 @@ use serde::Deserialize;
 /// - `domain`: 要管理的域名
 /// - `rrhost`: 记录主机名(如"@", "www"等)
 /// - `rrttl`: DNS记录TTL值
+/// - `connect_timeout_ms`: 建立连接超时(毫秒)
+/// - `timeout_ms`: 单次请求超时(毫秒)
+/// - `retries`: 网络错误或5xx响应时的最大重试次数
+/// - `backoff_ms`: 首次重试前的等待时间(毫秒)，之后每次翻倍
+/// - `max_backoff_ms`: 重试等待时间上限(毫秒)
 #[derive(Debug, Deserialize, Clone)]
 pub struct NamesiloConfig {
     pub url: String,
@@ -19,4 +35,67 @@ pub struct NamesiloConfig {
     pub domain: String,
     pub rrhost: String,
     pub rrttl: String,
+    #[serde(default = "default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    #[serde(default = "default_backoff_ms")]
+    pub backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+impl NamesiloConfig {
+    /// 获取目标记录的完整主机名(rrhost.domain)
+    pub fn target_host(&self) -> String {
+        format!("{}.{}", self.rrhost, self.domain)
+    }
+
+    /// 获取记录TTL，未配置时为Namesilo的默认值
+    pub fn ttl(&self) -> u32 {
+        self.rrttl.trim().parse().unwrap_or(DEFAULT_TTL)
+    }
+
+    /// 获取建立连接超时时间
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_millis(self.connect_timeout_ms)
+    }
+
+    /// 获取单次请求超时时间
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+
+    /// 获取第`retry`次重试(从0开始)前的最长等待时间
+    ///
+    /// 等待时间为`backoff_ms * 2^retry`，不超过`max_backoff_ms`
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .backoff_ms
+            .saturating_mul(2u64.saturating_pow(retry))
+            .min(self.max_backoff_ms);
+        Duration::from_millis(backoff)
+    }
+}
+
+fn default_connect_timeout_ms() -> u64 {
+    DEFAULT_CONNECT_TIMEOUT_MS
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_TIMEOUT_MS
+}
+
+fn default_retries() -> u32 {
+    DEFAULT_RETRIES
+}
+
+fn default_backoff_ms() -> u64 {
+    DEFAULT_BACKOFF_MS
+}
+
+fn default_max_backoff_ms() -> u64 {
+    DEFAULT_MAX_BACKOFF_MS
 }
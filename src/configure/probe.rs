@@ -0,0 +1,37 @@
+//! 探测配置模块
+//!
+//! 定义对单个IP进行TCP连接探测的参数
+
+use serde::Deserialize;
+use std::time::Duration;
+
+/// TCP连接探测配置
+///
+/// # 字段
+/// - `port`: 探测端口
+/// - `count`: 每次探测的连接次数
+/// - `timeout_ms`: 单次连接超时时间(毫秒)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ProbeConfig {
+    pub port: u16,
+    pub count: u32,
+    pub timeout_ms: u64,
+}
+
+impl Default for ProbeConfig {
+    fn default() -> Self {
+        Self {
+            port: 443,
+            count: 4,
+            timeout_ms: 1000,
+        }
+    }
+}
+
+impl ProbeConfig {
+    /// 获取单次连接超时时间
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
@@ -0,0 +1,94 @@
+//! DNS传播验证配置模块
+//!
+//! 定义DNS记录更新后直接查询权威服务器、确认新值生效的参数
+
+use anyhow::Context;
+use serde::Deserialize;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+/// DNS默认端口
+const DNS_PORT: u16 = 53;
+
+/// DNS传播验证配置
+///
+/// # 字段
+/// - `enabled`: 更新记录后是否验证传播
+/// - `nameservers`: 要查询的DNS服务器(`IP`或`IP:端口`，未指定端口时为53)
+/// - `transport`: 查询使用的传输协议(UDP响应被截断时自动改用TCP)
+/// - `timeout_ms`: 单次查询超时(毫秒)
+/// - `interval_ms`: 两轮查询之间的间隔(毫秒)
+/// - `deadline_secs`: 验证的最长时间(秒)，超过后视为未生效
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PropagationConfig {
+    pub enabled: bool,
+    pub nameservers: Vec<String>,
+    pub transport: Transport,
+    pub timeout_ms: u64,
+    pub interval_ms: u64,
+    pub deadline_secs: u64,
+}
+
+impl Default for PropagationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            nameservers: Vec::new(),
+            transport: Transport::Udp,
+            timeout_ms: 2000,
+            interval_ms: 2000,
+            deadline_secs: 60,
+        }
+    }
+}
+
+/// DNS查询传输协议
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    Udp,
+    Tcp,
+}
+
+impl PropagationConfig {
+    /// 解析要查询的DNS服务器地址
+    ///
+    /// # 返回值
+    /// - 成功: 返回服务器地址列表
+    /// - 失败: 存在格式错误的地址
+    pub fn servers(&self) -> anyhow::Result<Vec<SocketAddr>> {
+        self.nameservers
+            .iter()
+            .map(|server| parse_nameserver(server))
+            .collect()
+    }
+
+    /// 获取单次查询超时时间
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+
+    /// 获取两轮查询之间的间隔
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+
+    /// 获取验证的最长时间
+    pub fn deadline(&self) -> Duration {
+        Duration::from_secs(self.deadline_secs)
+    }
+}
+
+/// 解析DNS服务器地址(`IP`或`IP:端口`，未指定端口时为53)
+pub fn parse_nameserver(server: &str) -> anyhow::Result<SocketAddr> {
+    let server = server.trim();
+    server
+        .parse::<SocketAddr>()
+        .or_else(|_| {
+            server
+                .parse::<IpAddr>()
+                .map(|ip| SocketAddr::new(ip, DNS_PORT))
+        })
+        .with_context(|| format!("invalid nameserver: {}", server))
+}
@@ -0,0 +1,85 @@
+//! RFC 2136动态更新配置模块
+//!
+//! 定义通过TSIG签名的UPDATE报文修改自建权威服务器记录所需的配置项
+
+use anyhow::Context;
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// RFC 2136动态更新配置
+///
+/// # 字段
+/// - `server`: 主权威服务器地址(`IP:端口`)
+/// - `zone`: 区域名
+/// - `host`: 记录名(相对区域，"@"表示区域本身)
+/// - `record_type`: 记录类型(A或AAAA)
+/// - `ttl`: 更新后的记录TTL
+/// - `key_name`: TSIG密钥名
+/// - `algorithm`: TSIG算法
+/// - `secret`: TSIG密钥(Base64)
+/// - `timeout_ms`: 单次查询或更新超时(毫秒)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Rfc2136Config {
+    pub server: String,
+    pub zone: String,
+    pub host: String,
+    pub record_type: String,
+    pub ttl: u32,
+    pub key_name: String,
+    pub algorithm: TsigAlgorithm,
+    pub secret: String,
+    pub timeout_ms: u64,
+}
+
+impl Default for Rfc2136Config {
+    fn default() -> Self {
+        Self {
+            server: String::new(),
+            zone: String::new(),
+            host: "@".to_string(),
+            record_type: "A".to_string(),
+            ttl: 300,
+            key_name: String::new(),
+            algorithm: TsigAlgorithm::HmacSha256,
+            secret: String::new(),
+            timeout_ms: 5000,
+        }
+    }
+}
+
+/// TSIG签名算法
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum TsigAlgorithm {
+    #[serde(rename = "hmac-sha256")]
+    HmacSha256,
+    #[serde(rename = "hmac-sha384")]
+    HmacSha384,
+    #[serde(rename = "hmac-sha512")]
+    HmacSha512,
+}
+
+impl Rfc2136Config {
+    /// 获取目标记录的完整主机名(host.zone)
+    pub fn target_host(&self) -> String {
+        let zone = self.zone.trim_end_matches('.');
+        match self.host.trim_end_matches('.') {
+            "" | "@" => zone.to_string(),
+            host => format!("{}.{}", host, zone),
+        }
+    }
+
+    /// 解析主权威服务器地址
+    pub fn server(&self) -> anyhow::Result<SocketAddr> {
+        self.server
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid rfc2136 server: {}", self.server))
+    }
+
+    /// 获取单次查询或更新超时时间
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
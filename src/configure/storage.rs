@@ -0,0 +1,31 @@
+//! 存储配置模块
+//!
+//! 定义持久化数据的存放位置
+
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// 存储配置
+///
+/// # 字段
+/// - `dir`: 数据目录
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub dir: String,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            dir: "data".to_string(),
+        }
+    }
+}
+
+impl StorageConfig {
+    /// 获取数据目录下指定文件的路径
+    pub fn path(&self, name: &str) -> PathBuf {
+        PathBuf::from(&self.dir).join(name)
+    }
+}
@@ -0,0 +1,87 @@
+//! Webhook DNS服务商配置模块
+//!
+//! 定义通过调用自定义HTTP接口修改DNS记录所需的配置项
+
+use crate::configure::propagation;
+use anyhow::ensure;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// 默认请求体模板
+const DEFAULT_BODY: &str = r#"{"ip": "{{ip}}", "host": "{{host}}", "domain": "{{domain}}", "ttl": {{ttl}}, "type": "{{record_type}}"}"#;
+
+/// Webhook DNS服务商配置
+///
+/// # 字段
+/// - `url`: 更新记录时POST请求的地址
+/// - `body`: 请求体模板(JSON)，支持`{{ip}}`、`{{host}}`、`{{domain}}`、`{{ttl}}`、`{{record_type}}`占位符
+/// - `headers`: 附加的请求头
+/// - `secret`: HMAC-SHA256签名密钥，为空时不签名
+/// - `signature_header`: 签名请求头名称，值为`sha256=<请求体签名的十六进制>`
+/// - `domain`: 域名
+/// - `host`: 记录主机名(相对域名，"@"表示域名本身)
+/// - `record_type`: 记录类型(A或AAAA)
+/// - `ttl`: 更新后的记录TTL
+/// - `nameserver`: 查询记录当前值的权威DNS服务器(`IP`或`IP:端口`)，必须配置，不能使用递归解析器
+/// - `timeout_ms`: 单次请求或查询超时(毫秒)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub body: String,
+    pub headers: BTreeMap<String, String>,
+    pub secret: String,
+    pub signature_header: String,
+    pub domain: String,
+    pub host: String,
+    pub record_type: String,
+    pub ttl: u32,
+    pub nameserver: String,
+    pub timeout_ms: u64,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            body: DEFAULT_BODY.to_string(),
+            headers: BTreeMap::new(),
+            secret: String::new(),
+            signature_header: "X-Signature-256".to_string(),
+            domain: String::new(),
+            host: "@".to_string(),
+            record_type: "A".to_string(),
+            ttl: 300,
+            nameserver: String::new(),
+            timeout_ms: 10000,
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// 获取目标记录的完整主机名(host.domain)
+    pub fn target_host(&self) -> String {
+        let domain = self.domain.trim_end_matches('.');
+        match self.host.trim_end_matches('.') {
+            "" | "@" => domain.to_string(),
+            host => format!("{}.{}", host, domain),
+        }
+    }
+
+    /// 解析查询记录当前值的权威DNS服务器地址
+    pub fn nameserver(&self) -> anyhow::Result<SocketAddr> {
+        ensure!(
+            !self.nameserver.trim().is_empty(),
+            "webhook.nameserver is not set, configure an authoritative nameserver of {}",
+            self.domain
+        );
+        propagation::parse_nameserver(&self.nameserver)
+    }
+
+    /// 获取单次请求或查询超时时间
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
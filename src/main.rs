@@ -1,9 +1,12 @@
 mod api;
+mod catalog;
 mod client;
 mod configure;
 mod model;
+mod monitor;
 mod router;
 mod server;
+mod store;
 
 /// 应用主入口
 #[tokio::main(flavor = "multi_thread", worker_threads = 4)]
@@ -0,0 +1,155 @@
+//! DNS变更审计模型
+//!
+//! 定义DNS记录的变更记录及其来源，用于审计和回滚
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// DNS记录值
+///
+/// # 字段
+/// - `value`: 记录值
+/// - `ttl`: TTL(秒)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordValue {
+    pub value: String,
+    pub ttl: u32,
+}
+
+/// DNS变更记录存储
+///
+/// # 字段
+/// - `last_id`: 最近分配的变更ID，旧记录被清理后仍继续递增，保证ID不被复用
+/// - `changes`: 变更记录，按变更顺序排列
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DnsChangeLog {
+    pub last_id: u64,
+    pub changes: Vec<DnsChange>,
+}
+
+/// DNS变更记录
+///
+/// # 字段
+/// - `id`: 变更ID(递增)
+/// - `changed_at`: 变更时间
+/// - `host`: 记录主机名
+/// - `record_type`: 记录类型
+/// - `previous`: 变更前的记录值
+/// - `current`: 变更后的记录值
+/// - `trigger`: 触发方式
+/// - `requester`: 发起方(手动变更为请求头`X-Requester`或客户端地址)
+/// - `rollback_of`: 回滚时为被回滚的变更ID
+/// - `result`: 变更结果
+/// - `error`: 变更失败时的错误信息
+/// - `propagation`: DNS传播验证结果(开启验证且变更成功时才有)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsChange {
+    pub id: u64,
+    pub changed_at: DateTime<Local>,
+    pub host: String,
+    pub record_type: String,
+    pub previous: RecordValue,
+    pub current: RecordValue,
+    pub trigger: Trigger,
+    pub requester: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollback_of: Option<u64>,
+    pub result: ChangeResult,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub propagation: Option<Propagation>,
+}
+
+/// DNS变更来源
+///
+/// # 字段
+/// - `trigger`: 触发方式
+/// - `requester`: 发起方
+/// - `rollback_of`: 回滚时为被回滚的变更ID
+#[derive(Debug, Clone)]
+pub struct ChangeOrigin {
+    pub trigger: Trigger,
+    pub requester: String,
+    pub rollback_of: Option<u64>,
+}
+
+/// DNS变更触发方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    /// 通过API手动同步或回滚
+    Manual,
+    /// 定时任务(如cron)通过API同步
+    Schedule,
+    /// 已发布IP劣化时自动切换
+    Failover,
+}
+
+/// DNS变更结果
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeResult {
+    /// 变更成功
+    Success,
+    /// 调用DNS服务商失败
+    Failed,
+}
+
+/// DNS传播验证结果
+///
+/// # 字段
+/// - `status`: 验证状态
+/// - `elapsed_ms`: 验证耗时(毫秒)
+/// - `error`: 无法进行验证时的错误信息(如DNS服务器配置错误)
+/// - `servers`: 每个DNS服务器最后一次查询的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Propagation {
+    pub status: PropagationStatus,
+    pub elapsed_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub servers: Vec<ServerAnswer>,
+}
+
+impl Propagation {
+    /// 正在后台验证，尚无结果
+    pub fn pending() -> Self {
+        Self {
+            status: PropagationStatus::Pending,
+            elapsed_ms: 0,
+            error: None,
+            servers: Vec::new(),
+        }
+    }
+}
+
+/// DNS传播验证状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PropagationStatus {
+    /// 正在后台验证
+    Pending,
+    /// 所有DNS服务器都已返回新值
+    Propagated,
+    /// 超过最长验证时间仍有DNS服务器未返回新值
+    TimedOut,
+    /// 无法进行验证
+    Failed,
+}
+
+/// 单个DNS服务器的查询结果
+///
+/// # 字段
+/// - `server`: DNS服务器地址
+/// - `propagated`: 是否已返回新值
+/// - `values`: 返回的记录值
+/// - `error`: 查询失败时的错误信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerAnswer {
+    pub server: String,
+    pub propagated: bool,
+    pub values: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
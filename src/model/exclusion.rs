@@ -0,0 +1,41 @@
+//! IP排除列表模型
+//!
+//! 定义优选时需要跳过的IP或IP段
+
+use chrono::{DateTime, Local};
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+
+/// 排除项
+///
+/// # 字段
+/// - `target`: 被排除的IP段(单个IP为/32或/128)
+/// - `reason`: 排除原因
+/// - `source`: 排除来源
+/// - `created_at`: 创建时间
+/// - `expires_at`: 过期时间(为空表示永久有效)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exclusion {
+    pub target: IpNet,
+    pub reason: String,
+    pub source: ExclusionSource,
+    pub created_at: DateTime<Local>,
+    pub expires_at: Option<DateTime<Local>>,
+}
+
+impl Exclusion {
+    /// 判断排除项在指定时间是否仍然有效
+    pub fn is_active(&self, now: DateTime<Local>) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// 排除来源
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExclusionSource {
+    /// 手动添加
+    Manual,
+    /// 同步DNS后验证失败自动添加
+    AutoBlock,
+}
@@ -0,0 +1,220 @@
+//! 优选历史分析模型
+//!
+//! 定义跨任务分析的结果，包括:
+//! - 两次优选任务的对比
+//! - 单个IP的历史测量数据及其时间序列
+//! - 多次优选任务的稳定性排名
+
+use crate::model::monitor::Sample;
+use crate::model::select::IpResult;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// 两次优选任务的对比结果
+///
+/// # 字段
+/// - `from`: 基准任务ID
+/// - `to`: 对比任务ID
+/// - `top`: 参与对比的前N个IP
+/// - `winner`: 最优IP的变化
+/// - `entered`: 新进入前N的IP(按对比任务中的顺序)
+/// - `left`: 跌出前N的IP(按基准任务中的顺序)
+/// - `changed`: 两次任务中都存在的IP的指标变化(前N范围内)
+#[derive(Debug, Clone, Serialize)]
+pub struct JobDiff {
+    pub from: u64,
+    pub to: u64,
+    pub top: usize,
+    pub winner: WinnerChange,
+    pub entered: Vec<IpResult>,
+    pub left: Vec<IpResult>,
+    pub changed: Vec<IpChange>,
+}
+
+/// 最优IP的变化
+///
+/// # 字段
+/// - `from`: 基准任务的最优IP
+/// - `to`: 对比任务的最优IP
+/// - `changed`: 最优IP是否变化
+#[derive(Debug, Clone, Serialize)]
+pub struct WinnerChange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub changed: bool,
+}
+
+/// 单个IP在两次任务之间的变化
+///
+/// # 字段
+/// - `ip`: IP地址
+/// - `rank_from`: 在基准任务中的排名(从1开始)
+/// - `rank_to`: 在对比任务中的排名(从1开始)
+/// - `latency_ms`: 平均延迟(毫秒)的变化
+/// - `speed_mbps`: 下载速度(MB/s)的变化
+#[derive(Debug, Clone, Serialize)]
+pub struct IpChange {
+    pub ip: String,
+    pub rank_from: usize,
+    pub rank_to: usize,
+    pub latency_ms: Delta,
+    pub speed_mbps: Delta,
+}
+
+/// 指标在两次任务之间的变化
+///
+/// # 字段
+/// - `from`: 基准任务中的取值
+/// - `to`: 对比任务中的取值
+/// - `change`: 变化量(`to - from`)
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Delta {
+    pub from: f64,
+    pub to: f64,
+    pub change: f64,
+}
+
+impl Delta {
+    /// 根据两次取值计算变化
+    pub fn new(from: f64, to: f64) -> Self {
+        Self {
+            from,
+            to,
+            change: to - from,
+        }
+    }
+}
+
+/// 单次测量记录
+///
+/// 来自优选任务结果或已发布IP的监控探测
+///
+/// # 字段
+/// - `ip`: IP地址
+/// - `measured_at`: 测量时间
+/// - `job`: 所属优选任务ID(监控探测为空)
+/// - `loss`: 丢包率
+/// - `latency_ms`: 平均延迟(毫秒，全部连接失败时为空)
+/// - `speed_mbps`: 下载速度(MB/s，监控探测为空)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Measurement {
+    pub ip: String,
+    pub measured_at: DateTime<Local>,
+    pub job: Option<u64>,
+    pub loss: f64,
+    pub latency_ms: Option<f64>,
+    pub speed_mbps: Option<f64>,
+}
+
+impl Measurement {
+    /// 由优选任务的结果创建测量记录
+    pub fn from_result(job: u64, measured_at: DateTime<Local>, result: &IpResult) -> Self {
+        Self {
+            ip: result.ip.clone(),
+            measured_at,
+            job: Some(job),
+            loss: result.loss,
+            latency_ms: Some(result.latency_ms),
+            speed_mbps: Some(result.speed_mbps),
+        }
+    }
+
+    /// 由监控探测记录创建测量记录
+    pub fn from_sample(sample: &Sample) -> Self {
+        Self {
+            ip: sample.ip.clone(),
+            measured_at: sample.checked_at,
+            job: None,
+            loss: sample.probe.loss,
+            latency_ms: sample.probe.latency_ms,
+            speed_mbps: None,
+        }
+    }
+}
+
+/// 单个IP的历史时间序列
+///
+/// # 字段
+/// - `ip`: IP地址
+/// - `total`: 时间范围内的测量次数
+/// - `bucket_secs`: 聚合粒度(秒)，未降采样时为空
+/// - `points`: 按时间先后排列的数据点
+#[derive(Debug, Clone, Serialize)]
+pub struct IpHistory {
+    pub ip: String,
+    pub total: usize,
+    pub bucket_secs: Option<i64>,
+    pub points: Vec<HistoryPoint>,
+}
+
+/// 时间序列中的一个数据点
+///
+/// 未降采样时对应一次测量，降采样时为一个时间段内各指标的平均值
+///
+/// # 字段
+/// - `at`: 测量时间(降采样时为时间段起点)
+/// - `samples`: 包含的测量次数
+/// - `job`: 所属优选任务ID(仅未降采样时)
+/// - `loss`: 丢包率
+/// - `latency_ms`: 平均延迟(毫秒)
+/// - `speed_mbps`: 下载速度(MB/s)
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryPoint {
+    pub at: DateTime<Local>,
+    pub samples: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job: Option<u64>,
+    pub loss: f64,
+    pub latency_ms: Option<f64>,
+    pub speed_mbps: Option<f64>,
+}
+
+impl From<Measurement> for HistoryPoint {
+    fn from(m: Measurement) -> Self {
+        Self {
+            at: m.measured_at,
+            samples: 1,
+            job: m.job,
+            loss: m.loss,
+            latency_ms: m.latency_ms,
+            speed_mbps: m.speed_mbps,
+        }
+    }
+}
+
+/// 多次优选任务的稳定性排名
+///
+/// # 字段
+/// - `jobs`: 参与统计的任务ID(从新到旧)
+/// - `total`: 参与排名的IP数量
+/// - `items`: 按得分升序排列的IP(得分越低越好)
+#[derive(Debug, Clone, Serialize)]
+pub struct Ranking {
+    pub jobs: Vec<u64>,
+    pub total: usize,
+    pub items: Vec<RankedIp>,
+}
+
+/// 单个IP的稳定性排名
+///
+/// # 字段
+/// - `ip`: IP地址
+/// - `score`: 得分，`(延迟中位数 + 延迟标准差) / 可达比例`，越低越好
+/// - `reachable_runs`: 有有效结果的任务数量
+/// - `reachability`: 可达比例(有有效结果的任务数 / 参与统计的任务数)
+/// - `median_latency_ms`: 延迟中位数(毫秒)
+/// - `latency_stddev_ms`: 延迟标准差(毫秒)
+/// - `median_speed_mbps`: 下载速度中位数(MB/s)
+/// - `colo`: 最近一次结果中的地区码
+#[derive(Debug, Clone, Serialize)]
+pub struct RankedIp {
+    pub ip: String,
+    pub score: f64,
+    pub reachable_runs: usize,
+    pub reachability: f64,
+    pub median_latency_ms: f64,
+    pub latency_stddev_ms: f64,
+    pub median_speed_mbps: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colo: Option<String>,
+}
@@ -1,8 +1,18 @@
 //! 数据模型模块
 //!
 //! 包含应用核心数据结构定义：
+//! - `dns`: DNS变更审计模型
+//! - `exclusion`: IP排除列表模型
+//! - `history`: 优选历史分析模型
+//! - `monitor`: DNS记录监控模型
+//! - `preset`: IP段预设模型
 //! - `response`: API响应模型
 //! - `select`: IP选择状态模型
 
+pub mod dns;
+pub mod exclusion;
+pub mod history;
+pub mod monitor;
+pub mod preset;
 pub mod response;
 pub mod select;
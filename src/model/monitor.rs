@@ -0,0 +1,106 @@
+//! DNS记录监控模型
+//!
+//! 定义已发布IP的健康监控状态和探测历史
+
+use crate::client::probe::ProbeResult;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// 监控状态
+///
+/// # 字段
+/// - `enabled`: 是否启用监控
+/// - `host`: 监控的DNS记录主机名
+/// - `current_ip`: 记录当前指向的IP
+/// - `last_checked_at`: 最近一次检查时间
+/// - `last_error`: 最近一次检查的错误信息(成功时为空)
+/// - `samples`: 探测历史(按时间先后排列)
+/// - `degraded_count`: 当前IP连续劣化的次数
+/// - `last_failover`: 最近一次故障切换
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MonitorState {
+    pub enabled: bool,
+    pub host: String,
+    pub current_ip: Option<String>,
+    pub last_checked_at: Option<DateTime<Local>>,
+    pub last_error: Option<String>,
+    pub samples: VecDeque<Sample>,
+    pub degraded_count: u32,
+    pub last_failover: Option<Failover>,
+}
+
+/// 单次探测记录
+///
+/// # 字段
+/// - `checked_at`: 探测时间
+/// - `ip`: 被探测的IP
+/// - `probe`: 探测结果
+#[derive(Debug, Clone, Serialize)]
+pub struct Sample {
+    pub checked_at: DateTime<Local>,
+    pub ip: String,
+    #[serde(flatten)]
+    pub probe: ProbeResult,
+}
+
+/// 故障切换记录
+///
+/// # 字段
+/// - `switched_at`: 切换时间
+/// - `from`: 切换前的IP
+/// - `to`: 切换后的IP
+/// - `reason`: 切换原因
+/// - `dry_run`: 是否为演练(未修改DNS记录)
+#[derive(Debug, Clone, Serialize)]
+pub struct Failover {
+    pub switched_at: DateTime<Local>,
+    pub from: String,
+    pub to: String,
+    pub reason: String,
+    pub dry_run: bool,
+}
+
+/// 监控报告(监控状态及其统计)
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorReport {
+    #[serde(flatten)]
+    pub state: MonitorState,
+    pub summary: Summary,
+}
+
+/// 探测历史统计
+///
+/// # 字段
+/// - `count`: 样本数量
+/// - `avg_latency_ms`: 平均延迟(毫秒，不含全部失败的样本)
+/// - `avg_loss`: 平均丢包率
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub count: usize,
+    pub avg_latency_ms: Option<f64>,
+    pub avg_loss: Option<f64>,
+}
+
+impl MonitorState {
+    /// 统计当前IP的探测历史
+    pub fn summary(&self) -> Summary {
+        let samples: Vec<&Sample> = self
+            .samples
+            .iter()
+            .filter(|sample| Some(&sample.ip) == self.current_ip.as_ref())
+            .collect();
+        let latencies: Vec<f64> = samples.iter().filter_map(|s| s.probe.latency_ms).collect();
+
+        Summary {
+            count: samples.len(),
+            avg_latency_ms: average(&latencies),
+            avg_loss: average(&samples.iter().map(|s| s.probe.loss).collect::<Vec<_>>()),
+        }
+    }
+}
+
+/// 计算平均值，空列表返回None
+fn average(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
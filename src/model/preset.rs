@@ -0,0 +1,21 @@
+//! IP段预设模型
+//!
+//! 定义服务端保存的命名IP段集合
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// IP段预设
+///
+/// # 字段
+/// - `name`: 预设名称(唯一)
+/// - `ranges`: IP段列表
+/// - `created_at`: 创建时间
+/// - `updated_at`: 最近修改时间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub ranges: Vec<String>,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
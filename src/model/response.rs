@@ -114,6 +114,22 @@ pub enum Code {
     Success = 0,
     /// 响应序列化失败
     RespSerializeFailed = 100,
+    /// 请求参数错误
+    InvalidParameter = 400,
+    /// 未授权(如DNS服务商API Key无效)
+    Unauthorized = 401,
+    /// 无权限(如DNS服务商拒绝当前IP访问)
+    Forbidden = 403,
+    /// 资源不存在
+    NotFound = 404,
+    /// 资源已存在
+    Conflict = 409,
+    /// 请求过于频繁(DNS服务商限流)
+    TooManyRequests = 429,
     /// 内部服务器错误
     InternalError = 500,
+    /// 上游服务错误(DNS服务商返回错误)
+    BadGateway = 502,
+    /// 服务繁忙(任务队列已满)
+    Busy = 503,
 }
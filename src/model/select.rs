@@ -1,18 +1,233 @@
 //! 选择状态模型
 //!
-//! 定义IP选择任务的状态枚举
+//! 定义IP选择任务的状态、任务队列和优选结果
 
-use serde::Serialize;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
 
 /// IP选择任务状态
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum Status {
-    /// 等待中(初始状态)
+    /// 等待中(初始状态或排队中)
     Pending,
     /// 处理中(任务进行中)
     Processing,
     /// 成功(任务完成)
     Success,
-    /// 失败(包含错误信息)
-    Failed(String),
+    /// 失败(详见任务的失败信息)
+    Failed,
+    /// 超时(超过配置的时长被终止)
+    TimedOut,
+}
+
+/// 测速后端
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum Prober {
+    /// CloudflareSpeedTest命令行工具
+    CloudflareSpeedTest,
+}
+
+/// 任务来源
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum JobSource {
+    /// 本机执行的优选任务
+    #[default]
+    Select,
+    /// 导入的CloudflareSpeedTest结果文件
+    Import,
+}
+
+/// 优选任务信息
+///
+/// # 字段
+/// - `id`: 任务ID
+/// - `state`: 任务状态
+/// - `created_at`: 提交时间
+/// - `started_at`: 开始执行时间(排队中为空)
+/// - `finished_at`: 结束时间(未结束为空)
+/// - `ranges`: 剔除排除项后的IP段数量
+/// - `ips`: IP段包含的IP总数
+/// - `prober`: 测速后端
+/// - `source`: 任务来源
+/// - `result_count`: 有效结果数量(成功后才有)
+/// - `failure`: 失败信息(失败或超时才有)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub id: u64,
+    pub state: Status,
+    pub created_at: DateTime<Local>,
+    pub started_at: Option<DateTime<Local>>,
+    pub finished_at: Option<DateTime<Local>>,
+    pub ranges: usize,
+    pub ips: u128,
+    pub prober: Prober,
+    #[serde(default)]
+    pub source: JobSource,
+    pub result_count: Option<usize>,
+    pub failure: Option<Failure>,
+}
+
+impl JobInfo {
+    /// 创建排队中的任务
+    pub fn new(id: u64, ranges: usize, ips: u128) -> Self {
+        Self {
+            id,
+            state: Status::Pending,
+            created_at: Local::now(),
+            started_at: None,
+            finished_at: None,
+            ranges,
+            ips,
+            prober: Prober::CloudflareSpeedTest,
+            source: JobSource::Select,
+            result_count: None,
+            failure: None,
+        }
+    }
+
+    /// 创建导入的任务(已成功结束)
+    ///
+    /// # 参数
+    /// - `id`: 任务ID
+    /// - `count`: 有效结果数量
+    pub fn imported(id: u64, count: usize) -> Self {
+        let now = Local::now();
+        Self {
+            state: Status::Success,
+            created_at: now,
+            started_at: Some(now),
+            finished_at: Some(now),
+            source: JobSource::Import,
+            result_count: Some(count),
+            ..Self::new(id, 0, 0)
+        }
+    }
+
+    /// 标记任务开始执行
+    pub fn start(&mut self) {
+        self.state = Status::Processing;
+        self.started_at = Some(Local::now());
+    }
+
+    /// 标记任务结束
+    ///
+    /// # 参数
+    /// - `outcome`: 成功时为有效结果数量，失败时为失败信息
+    pub fn finish(&mut self, outcome: Result<usize, Failure>) {
+        self.finished_at = Some(Local::now());
+        match outcome {
+            Ok(count) => {
+                self.state = Status::Success;
+                self.result_count = Some(count);
+            }
+            Err(failure) => {
+                self.state = match failure.kind {
+                    FailureKind::Timeout => Status::TimedOut,
+                    _ => Status::Failed,
+                };
+                self.failure = Some(failure);
+            }
+        }
+    }
+
+    /// 已执行时长(秒)，进行中的任务计算到当前时间
+    pub fn elapsed_secs(&self) -> Option<f64> {
+        let started_at = self.started_at?;
+        let finished_at = self.finished_at.unwrap_or_else(Local::now);
+        Some((finished_at - started_at).as_seconds_f64())
+    }
+}
+
+/// 任务失败信息
+///
+/// # 字段
+/// - `kind`: 失败类型
+/// - `message`: 错误信息
+/// - `exit_code`: 测速进程退出码(进程异常退出时才有)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Failure {
+    pub kind: FailureKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+}
+
+impl Failure {
+    /// 创建失败信息
+    pub fn new(kind: FailureKind, message: impl ToString) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+            exit_code: None,
+        }
+    }
+}
+
+/// 任务失败类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum FailureKind {
+    /// 写入测速输入文件失败
+    Input,
+    /// 启动测速进程失败
+    Spawn,
+    /// 测速进程异常退出
+    Exit,
+    /// 超时被终止
+    Timeout,
+    /// 读取或解析结果文件失败
+    Result,
+}
+
+/// 排队中的优选任务
+///
+/// # 字段
+/// - `info`: 任务信息
+/// - `ranges`: 要测试的IP段
+#[derive(Debug, Clone)]
+pub struct QueuedJob {
+    pub info: JobInfo,
+    pub ranges: Vec<String>,
+}
+
+/// 优选任务状态查询结果
+///
+/// # 字段
+/// - `version`: 状态版本号，每次状态变化递增
+/// - `status`: 当前任务状态(还没有任务时为Pending)
+/// - `job`: 当前或最近一次执行的任务
+/// - `elapsed_secs`: 当前或最近一次任务已执行的时长(秒)
+/// - `timeout_secs`: 任务超时时间(秒)
+/// - `queue_depth`: 队列容量
+/// - `queue`: 排队中的任务(按执行顺序排列)
+#[derive(Debug, Clone, Serialize)]
+pub struct SelectStatus {
+    pub version: u64,
+    pub status: Status,
+    pub job: Option<JobInfo>,
+    pub elapsed_secs: Option<f64>,
+    pub timeout_secs: u64,
+    pub queue_depth: usize,
+    pub queue: Vec<JobInfo>,
+}
+
+/// 单个IP的优选结果(对应CloudflareSpeedTest结果文件中的一行)
+///
+/// # 字段
+/// - `ip`: IP地址
+/// - `sent`: 已发送
+/// - `received`: 已接收
+/// - `loss`: 丢包率
+/// - `latency_ms`: 平均延迟(毫秒)
+/// - `speed_mbps`: 下载速度(MB/s)
+/// - `colo`: 地区码(新版本CloudflareSpeedTest才有)
+#[derive(Debug, Clone, Serialize)]
+pub struct IpResult {
+    pub ip: String,
+    pub sent: u32,
+    pub received: u32,
+    pub loss: f64,
+    pub latency_ms: f64,
+    pub speed_mbps: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colo: Option<String>,
 }
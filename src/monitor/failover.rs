@@ -0,0 +1,316 @@
+//! 故障切换模块
+//!
+//! 已发布IP连续多次超过延迟或丢包阈值时:
+//! 1. 从最近一次优选结果中按顺序选取候选IP(跳过当前IP和排除列表中的IP)
+//! 2. 逐个探测候选IP，选择第一个未劣化的IP
+//! 3. 将DNS记录切换到该IP
+//!
+//! 切换后进入冷却期，冷却期内不会再次切换，避免来回抖动。
+//! 开启演练模式时只在日志中记录变更计划，不修改DNS记录，但同样记录切换并进入冷却期
+
+use crate::api;
+use crate::client::namesilo::ResourceRecord;
+use crate::client::{probe, provider};
+use crate::model::dns::{ChangeOrigin, Trigger};
+use crate::model::monitor::{Failover, Sample};
+use crate::server::state::AppState;
+use chrono::Local;
+use std::net::IpAddr;
+use tracing::{info, warn};
+
+/// 根据最新探测结果判断是否需要切换，需要时执行切换
+///
+/// # 参数
+/// - `state`: 应用状态
+/// - `record`: 目标DNS记录
+/// - `sample`: 当前IP的最新探测结果
+///
+/// # 返回值
+/// - 成功: Ok(())
+/// - 失败: 读取优选结果或更新DNS记录失败
+pub async fn evaluate(
+    state: &AppState,
+    record: &ResourceRecord,
+    sample: &Sample,
+) -> anyhow::Result<()> {
+    let conf = &state.config.failover;
+    let degraded = conf.is_degraded(sample.probe.loss, sample.probe.latency_ms);
+
+    let degraded_count = {
+        let mut monitor = state.monitor.write().await;
+        monitor.degraded_count = if degraded {
+            monitor.degraded_count + 1
+        } else {
+            0
+        };
+        monitor.degraded_count
+    };
+    if !conf.enabled || degraded_count < conf.consecutive {
+        return Ok(());
+    }
+
+    // 冷却期内不切换
+    let now = Local::now();
+    if let Some(last) = &state.monitor.read().await.last_failover
+        && now - last.switched_at < conf.cooldown()
+    {
+        info!("failover skipped: in cooldown since {}", last.switched_at);
+        return Ok(());
+    }
+
+    let latency = sample
+        .probe
+        .latency_ms
+        .map_or("timeout".to_string(), |latency| {
+            format!("{:.1} ms", latency)
+        });
+    let reason = format!(
+        "{} degraded for {} consecutive checks (loss {:.2}, latency {})",
+        sample.ip, degraded_count, sample.probe.loss, latency
+    );
+    warn!("failover triggered: {}", reason);
+
+    let Some(ip) = pick_candidate(state, &sample.ip).await? else {
+        warn!("failover aborted: no healthy candidate");
+        return Ok(());
+    };
+
+    let origin = ChangeOrigin {
+        trigger: Trigger::Failover,
+        requester: "monitor".to_string(),
+        rollback_of: None,
+    };
+    let ttl = provider::ttl(&state.config);
+    let reply = api::dns::update_record(state, record, ip, ttl, conf.dry_run, origin).await?;
+
+    // 演练同样重新计数并进入冷却期，避免每次检查都重复探测候选IP
+    let mut monitor = state.monitor.write().await;
+    monitor.degraded_count = 0;
+    monitor.last_failover = Some(Failover {
+        switched_at: now,
+        from: sample.ip.clone(),
+        to: ip.to_string(),
+        reason,
+        dry_run: conf.dry_run,
+    });
+    if conf.dry_run {
+        warn!("failover dry run, record not changed: {}", reply.plan);
+        return Ok(());
+    }
+
+    monitor.current_ip = Some(ip.to_string());
+    info!("failover switched {} -> {}", sample.ip, ip);
+
+    Ok(())
+}
+
+/// 从最近一次优选结果中选出可用的候选IP
+///
+/// 按优选顺序逐个探测，返回第一个未超过阈值的IP
+///
+/// # 参数
+/// - `state`: 应用状态
+/// - `current`: 当前IP
+///
+/// # 返回值
+/// - 成功: 返回候选IP，没有可用候选时返回None
+/// - 失败: 读取优选结果失败
+async fn pick_candidate(state: &AppState, current: &str) -> anyhow::Result<Option<IpAddr>> {
+    let conf = &state.config.failover;
+    let exclusions = api::exclusion::active(state).await;
+
+    let candidates = api::ip::latest_results(state)
+        .await?
+        .into_iter()
+        .filter_map(|result| result.ip.parse::<IpAddr>().ok())
+        .filter(|ip| ip.to_string() != current)
+        .filter(|ip| !exclusions.iter().any(|net| net.contains(ip)))
+        .take(conf.candidates);
+
+    for ip in candidates {
+        // 切换前重新验证候选IP
+        let result = probe::tcp_probe(&state.config.probe, ip).await;
+        if conf.is_degraded(result.loss, result.latency_ms) {
+            info!("failover candidate {} rejected: {:?}", ip, result);
+            continue;
+        }
+
+        info!("failover candidate {} accepted: {:?}", ip, result);
+        return Ok(Some(ip));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::namesilo::{self, tests::Requests};
+    use crate::client::probe::ProbeResult;
+    use crate::configure::AppConfig;
+    use crate::model::select::JobInfo;
+    use tokio::net::TcpListener;
+
+    const UPDATE_OK: &str = r#"{"reply":{"code":300,"detail":"success"}}"#;
+
+    /// 最近一次优选结果，依次为当前IP、不可达的候选IP和可达的候选IP
+    const RESULTS: &str = "IP 地址,已发送,已接收,丢包率,平均延迟,下载速度 (MB/s),地区码\n\
+                           127.0.0.1,4,4,0.00,100.00,10.00,SJC\n\
+                           127.0.0.2,4,4,0.00,110.00,10.00,SJC\n\
+                           127.0.0.3,4,4,0.00,120.00,10.00,SJC\n";
+
+    /// 使用本地Namesilo替身的应用状态，只有127.0.0.3监听探测端口
+    async fn state(toml: &str) -> (AppState, Requests) {
+        let listener = TcpListener::bind("127.0.0.3:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            loop {
+                let _ = listener.accept().await;
+            }
+        });
+
+        let (namesilo, requests) = namesilo::tests::serve(vec![(200, UPDATE_OK)]).await;
+        let mut config = AppConfig::test(&format!(
+            "[failover]\nenabled = true\nconsecutive = 2\n{}\n[exclusion]\nauto_block = false",
+            toml
+        ));
+        config.namesilo = namesilo;
+        config.probe.port = port;
+        config.probe.count = 2;
+        let state = AppState::new(config).await.unwrap();
+        state
+            .job_history
+            .record(JobInfo::imported(1, 3), Some(RESULTS))
+            .await
+            .unwrap();
+        (state, requests)
+    }
+
+    /// 当前指向127.0.0.1的A记录
+    fn record() -> ResourceRecord {
+        ResourceRecord {
+            record_id: "r1".to_string(),
+            record_type: "A".to_string(),
+            host: "t.example.xyz".to_string(),
+            value: "127.0.0.1".to_string(),
+            ttl: 7207,
+        }
+    }
+
+    /// 当前IP的探测结果
+    fn sample(loss: f64, latency_ms: Option<f64>) -> Sample {
+        Sample {
+            checked_at: Local::now(),
+            ip: "127.0.0.1".to_string(),
+            probe: ProbeResult {
+                sent: 4,
+                received: 4 - (loss * 4.0) as u32,
+                loss,
+                latency_ms,
+            },
+        }
+    }
+
+    /// 测试连续劣化达到次数后切换，跳过当前IP和未通过重新验证的候选IP
+    #[tokio::test]
+    async fn test_failover_switches() {
+        let (state, requests) = state("").await;
+
+        evaluate(&state, &record(), &sample(1.0, None))
+            .await
+            .unwrap();
+        assert_eq!(state.monitor.read().await.degraded_count, 1);
+        assert!(requests.lock().unwrap().is_empty());
+
+        evaluate(&state, &record(), &sample(0.0, Some(500.0)))
+            .await
+            .unwrap();
+        let monitor = state.monitor.read().await;
+        assert_eq!(monitor.degraded_count, 0);
+        assert_eq!(monitor.current_ip.as_deref(), Some("127.0.0.3"));
+        let failover = monitor.last_failover.as_ref().unwrap();
+        assert_eq!(
+            (failover.from.as_str(), failover.to.as_str()),
+            ("127.0.0.1", "127.0.0.3")
+        );
+        assert!(!failover.dry_run);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].contains("dnsUpdateRecord"));
+        assert!(requests[0].contains("rrvalue=127.0.0.3"));
+    }
+
+    /// 测试恢复正常后重新计数
+    #[tokio::test]
+    async fn test_recovery_resets_count() {
+        let (state, requests) = state("").await;
+
+        evaluate(&state, &record(), &sample(1.0, None))
+            .await
+            .unwrap();
+        evaluate(&state, &record(), &sample(0.0, Some(100.0)))
+            .await
+            .unwrap();
+        assert_eq!(state.monitor.read().await.degraded_count, 0);
+        evaluate(&state, &record(), &sample(1.0, None))
+            .await
+            .unwrap();
+        assert_eq!(state.monitor.read().await.degraded_count, 1);
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    /// 测试冷却期内不再切换，连续劣化次数继续累计
+    #[tokio::test]
+    async fn test_cooldown_suppresses() {
+        let (state, requests) = state("").await;
+
+        for _ in 0..2 {
+            evaluate(&state, &record(), &sample(1.0, None))
+                .await
+                .unwrap();
+        }
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        for _ in 0..3 {
+            evaluate(&state, &record(), &sample(1.0, None))
+                .await
+                .unwrap();
+        }
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert_eq!(state.monitor.read().await.degraded_count, 3);
+    }
+
+    /// 测试候选IP都未通过重新验证时不切换
+    #[tokio::test]
+    async fn test_no_healthy_candidate() {
+        let (state, requests) = state("candidates = 1").await;
+
+        for _ in 0..2 {
+            evaluate(&state, &record(), &sample(1.0, None))
+                .await
+                .unwrap();
+        }
+        assert_eq!(pick_candidate(&state, "127.0.0.1").await.unwrap(), None);
+        let monitor = state.monitor.read().await;
+        assert!(monitor.last_failover.is_none());
+        assert_eq!(monitor.degraded_count, 2);
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    /// 测试演练模式只记录切换，不修改DNS记录
+    #[tokio::test]
+    async fn test_dry_run() {
+        let (state, requests) = state("dry_run = true").await;
+
+        for _ in 0..2 {
+            evaluate(&state, &record(), &sample(1.0, None))
+                .await
+                .unwrap();
+        }
+        let monitor = state.monitor.read().await;
+        assert!(monitor.last_failover.as_ref().unwrap().dry_run);
+        assert_eq!(monitor.current_ip, None);
+        assert!(requests.lock().unwrap().is_empty());
+    }
+}
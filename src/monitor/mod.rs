@@ -0,0 +1,195 @@
+//! DNS记录健康监控模块
+//!
+//! 后台周期性地:
+//! 1. 从DNS服务商查询目标记录当前指向的IP
+//! 2. 对该IP进行TCP连接探测
+//! 3. 记录延迟和丢包历史，超出容量时丢弃最旧的记录，同时写入该IP的测量历史
+//! 4. 按故障切换策略判断是否需要切换到候选IP
+
+use crate::client::{probe, provider};
+use crate::model::history::Measurement;
+use crate::model::monitor::Sample;
+use crate::server::state::AppState;
+use chrono::Local;
+use std::net::IpAddr;
+use tokio::time::{MissedTickBehavior, interval};
+use tracing::{info, warn};
+
+pub mod failover;
+
+/// 启动后台监控任务
+///
+/// 未启用监控时直接返回
+///
+/// # 参数
+/// - `state`: 应用状态
+pub async fn spawn(state: AppState) {
+    let conf = &state.config.monitor;
+    {
+        let mut monitor = state.monitor.write().await;
+        monitor.enabled = conf.enabled;
+        monitor.host = provider::target_host(&state.config);
+    }
+    if !conf.enabled {
+        return;
+    }
+
+    info!(
+        "dns monitor started for {} every {}s",
+        provider::target_host(&state.config),
+        conf.interval_secs
+    );
+    tokio::spawn(async move {
+        let mut ticker = interval(state.config.monitor.interval());
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            tick(&state).await;
+        }
+    });
+}
+
+/// 执行一次检查，失败时记录错误信息
+///
+/// # 参数
+/// - `state`: 应用状态
+async fn tick(state: &AppState) {
+    if let Err(e) = check(state).await {
+        warn!("dns monitor check failed: {}", e);
+        let mut monitor = state.monitor.write().await;
+        monitor.last_checked_at = Some(Local::now());
+        monitor.last_error = Some(e.to_string());
+    }
+}
+
+/// 执行一次检查并按需故障切换
+///
+/// # 参数
+/// - `state`: 应用状态
+///
+/// # 返回值
+/// - 成功: Ok(())
+/// - 失败: 记录不存在、记录值不是IP或查询失败
+async fn check(state: &AppState) -> anyhow::Result<()> {
+    let record = provider::find(&state.config)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("target host not found"))?;
+    let ip: IpAddr = record
+        .value
+        .parse()
+        .map_err(|_| anyhow::anyhow!("record value is not an ip: {}", record.value))?;
+
+    let result = probe::tcp_probe(&state.config.probe, ip).await;
+    let sample = Sample {
+        checked_at: Local::now(),
+        ip: ip.to_string(),
+        probe: result,
+    };
+    info!("dns monitor sample: {:?}", sample);
+
+    {
+        let mut monitor = state.monitor.write().await;
+        if monitor.current_ip.as_ref() != Some(&sample.ip) {
+            monitor.degraded_count = 0;
+        }
+        monitor.current_ip = Some(sample.ip.clone());
+        monitor.last_checked_at = Some(sample.checked_at);
+        monitor.last_error = None;
+        monitor.samples.push_back(sample.clone());
+        while monitor.samples.len() > state.config.monitor.history_size {
+            monitor.samples.pop_front();
+        }
+    }
+
+    if let Err(e) = state
+        .measurements
+        .append(&[Measurement::from_sample(&sample)])
+        .await
+    {
+        warn!("Failed to record monitor sample: {}", e);
+    }
+
+    failover::evaluate(state, &record, &sample).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::namesilo;
+    use crate::configure::AppConfig;
+    use tokio::net::TcpListener;
+
+    const LIST: &str = r#"{"reply":{"code":300,"detail":"success","resource_record":[
+        {"record_id":"r1","type":"A","host":"t.example.xyz","value":"127.0.0.1","ttl":7207}
+    ]}}"#;
+    const LIST_EMPTY: &str = r#"{"reply":{"code":300,"detail":"success","resource_record":[]}}"#;
+
+    /// 使用本地Namesilo替身和本地探测端口的应用状态
+    async fn state(responses: Vec<(u16, &'static str)>, history_size: usize) -> AppState {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            loop {
+                let _ = listener.accept().await;
+            }
+        });
+
+        let (namesilo, _) = namesilo::tests::serve(responses).await;
+        let mut config = AppConfig::test("");
+        config.namesilo = namesilo;
+        config.probe.port = port;
+        config.probe.count = 2;
+        config.monitor.history_size = history_size;
+        AppState::new(config).await.unwrap()
+    }
+
+    /// 测试每次检查都探测记录当前的IP，记录探测历史(超出容量时丢弃最旧的)和测量历史
+    #[tokio::test]
+    async fn test_check_records_samples() {
+        let state = state(vec![(200, LIST)], 2).await;
+        for _ in 0..3 {
+            tick(&state).await;
+        }
+
+        let monitor = state.monitor.read().await;
+        assert_eq!(monitor.current_ip.as_deref(), Some("127.0.0.1"));
+        assert!(monitor.last_checked_at.is_some());
+        assert_eq!(monitor.last_error, None);
+        assert_eq!(monitor.samples.len(), 2);
+        assert!(
+            monitor
+                .samples
+                .iter()
+                .all(|sample| sample.probe.received == 2)
+        );
+        assert_eq!(monitor.degraded_count, 0);
+
+        let measurements = state
+            .measurements
+            .query("127.0.0.1", None, None)
+            .await
+            .unwrap();
+        assert_eq!(measurements.len(), 3);
+    }
+
+    /// 测试查询失败时记录错误信息，不产生探测记录
+    #[tokio::test]
+    async fn test_check_failure_recorded() {
+        let state = state(vec![(200, LIST_EMPTY)], 2).await;
+        tick(&state).await;
+
+        let monitor = state.monitor.read().await;
+        assert_eq!(monitor.last_error.as_deref(), Some("target host not found"));
+        assert!(monitor.last_checked_at.is_some());
+        assert!(monitor.samples.is_empty());
+        assert_eq!(monitor.current_ip, None);
+    }
+
+    /// 测试检查间隔为0的配置在加载时被拒绝，不会启动监控任务
+    #[test]
+    fn test_zero_interval_rejected() {
+        assert!(AppConfig::test("").monitor.validate().is_ok());
+        let config = AppConfig::test("[monitor]\ninterval_secs = 0");
+        assert!(config.monitor.validate().is_err());
+    }
+}
@@ -1,12 +1,22 @@
 //! DNS同步相关路由模块
 //!
 //! 提供以下API端点:
-//! - POST /dns/sync: 同步DNS记录到Namesilo
+//! - POST /dns/sync: 同步DNS记录到DNS服务商(`dry_run=true`时只返回变更计划，定时任务调用时`trigger=schedule`)
+//! - GET /dns/monitor: 查询已发布IP的监控状态
+//! - GET /dns/history: 查询DNS变更记录
+//! - POST /dns/rollback/{id}: 将记录恢复为指定变更之前的值和TTL
 
-use crate::{api::dns::sync, server::state::AppState};
-use axum::routing::post;
+use crate::{
+    api::dns::{history, monitor, rollback, sync},
+    server::state::AppState,
+};
+use axum::routing::{get, post};
 
 /// 配置DNS同步路由
 pub fn setup(router: axum::Router<AppState>) -> axum::Router<AppState> {
-    router.route("/dns/sync", post(sync))
+    router
+        .route("/dns/sync", post(sync))
+        .route("/dns/monitor", get(monitor))
+        .route("/dns/history", get(history))
+        .route("/dns/rollback/{id}", post(rollback))
 }
@@ -2,10 +2,28 @@
 //!
 //! 提供以下API端点:
 //! - POST /ip/select: 启动IP优选任务
-//! - GET /ip/select: 获取优选结果
+//! - GET /ip/select: 获取优选结果(旧版本格式)
+//! - GET /ip/results: 过滤、排序、分页查询优选结果
 //! - GET /ip/select/status: 查询优选任务状态
+//! - GET /ip/best: 获取最优IP(支持纯文本格式)
+//! - GET /ip/export: 导出优选结果(CSV、NDJSON、Prometheus)
+//! - POST /ip/import: 导入CloudflareSpeedTest结果文件(multipart)
+//! - GET /ip/jobs/{a}/diff/{b}: 对比两次优选任务的前N个IP
+//! - GET /ip/{ip}/history: 获取单个IP的历史时间序列
+//! - GET /ip/ranking: 获取最近多次优选任务的稳定性排名
+//! - GET /ip/ranges: 获取内置Cloudflare IP段目录
+//! - POST /ip/ranges/refresh: 刷新内置Cloudflare IP段目录
+//! - GET /ip/presets: 获取IP段预设列表
+//! - POST /ip/presets: 创建IP段预设
+//! - GET/PUT/DELETE /ip/presets/{name}: 查询、修改、删除IP段预设
+//! - GET /ip/exclusions: 获取IP排除列表
+//! - POST /ip/exclusions: 添加排除项
+//! - DELETE /ip/exclusions?target=: 移除排除项
 
-use crate::api::ip::{select, selected, status};
+use crate::api::ip::{
+    best, export, import, ranges, refresh_ranges, results, select, selected, status,
+};
+use crate::api::{exclusion, job, preset};
 use crate::server::state::AppState;
 use axum::Router;
 use axum::routing::*;
@@ -17,6 +35,26 @@ pub fn setup(router: axum::Router<AppState>) -> axum::Router<AppState> {
         Router::new()
             .route("/select", post(select))
             .route("/select", get(selected))
-            .route("/select/status", get(status)),
+            .route("/select/status", get(status))
+            .route("/results", get(results))
+            .route("/best", get(best))
+            .route("/export", get(export))
+            .route("/import", post(import))
+            .route("/jobs/{from}/diff/{to}", get(job::diff))
+            .route("/{ip}/history", get(job::history))
+            .route("/ranking", get(job::ranking))
+            .route("/ranges", get(ranges))
+            .route("/ranges/refresh", post(refresh_ranges))
+            .route("/presets", get(preset::list).post(preset::create))
+            .route(
+                "/presets/{name}",
+                get(preset::get).put(preset::update).delete(preset::delete),
+            )
+            .route(
+                "/exclusions",
+                get(exclusion::list)
+                    .post(exclusion::add)
+                    .delete(exclusion::remove),
+            ),
     )
 }
@@ -6,8 +6,10 @@
 //! - 优雅关闭处理
 
 use crate::configure::AppConfig;
+use crate::monitor;
 use crate::router;
 use crate::server::state::AppState;
+use std::net::SocketAddr;
 use tokio::signal;
 use tracing::info;
 
@@ -27,15 +29,22 @@ pub async fn run() -> anyhow::Result<()> {
     // 3. 创建共享应用状态
     let state = AppState::new(conf.clone()).await?;
 
-    // 4. 配置路由
+    // 4. 启动后台DNS记录监控
+    monitor::spawn(state.clone()).await;
+
+    // 5. 配置路由
     let app = router::setup(state);
 
-    // 5. 绑定监听地址
+    // 6. 绑定监听地址
     let listener = tokio::net::TcpListener::bind(conf.listen.get_socket_addr()?).await?;
     info!("🚀 listening on {}", &listener.local_addr()?);
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    // 记录客户端地址，作为DNS变更的发起方
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
     Ok(())
 }
@@ -0,0 +1,301 @@
+//! 优选任务状态机模块
+//!
+//! 负责:
+//! - 维护当前任务和等待队列
+//! - 通过原子的比较并设置(CAS)完成状态转换，避免并发请求同时启动任务
+//! - 通过`watch`通道广播状态变化，每次变化递增版本号
+//!
+//! # 状态转换
+//! - 空闲(`Pending`/`Success`/`Failed`/`TimedOut`) -> `Processing`: 提交任务
+//! - `Processing` -> `Processing`: 任务进行中提交任务进入队列，或任务结束后取出下一个任务
+//! - `Processing` -> 结束状态: 任务结束且队列为空
+
+use crate::model::select::{JobInfo, QueuedJob, Status};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::timeout;
+
+/// 任务状态快照
+///
+/// # 字段
+/// - `current`: 当前或最近一次执行的任务
+/// - `queue`: 等待执行的任务
+/// - `version`: 状态版本号，每次变化递增
+/// - `last_id`: 最近分配的任务ID
+#[derive(Debug, Clone)]
+pub struct JobState {
+    pub current: Option<JobInfo>,
+    pub queue: VecDeque<QueuedJob>,
+    pub version: u64,
+    last_id: u64,
+}
+
+impl JobState {
+    /// 当前状态，还没有任务时为Pending
+    pub fn status(&self) -> Status {
+        self.current
+            .as_ref()
+            .map_or(Status::Pending, |job| job.state)
+    }
+}
+
+/// 提交任务的结果
+#[derive(Debug)]
+pub enum Submit {
+    /// 当前空闲，任务已开始执行
+    Started(JobInfo),
+    /// 已有任务进行中，进入队列的位置(从1开始)
+    Queued(JobInfo, usize),
+    /// 队列已满
+    Busy,
+}
+
+/// 优选任务状态机
+pub struct JobMachine {
+    tx: watch::Sender<JobState>,
+    queue_depth: usize,
+}
+
+impl JobMachine {
+    /// 创建状态机
+    ///
+    /// # 参数
+    /// - `queue_depth`: 等待队列容量
+    /// - `last_id`: 已分配的最大任务ID
+    pub fn new(queue_depth: usize, last_id: u64) -> Self {
+        let (tx, _) = watch::channel(JobState {
+            current: None,
+            queue: VecDeque::new(),
+            version: 0,
+            last_id,
+        });
+
+        Self { tx, queue_depth }
+    }
+
+    /// 获取当前状态快照
+    pub fn snapshot(&self) -> JobState {
+        self.tx.borrow().clone()
+    }
+
+    /// 等待状态版本号变化
+    ///
+    /// 当前版本号已不等于`since`时立即返回，否则最多等待`wait`
+    ///
+    /// # 参数
+    /// - `since`: 调用方已知的版本号
+    /// - `wait`: 最长等待时间
+    ///
+    /// # 返回值
+    /// 返回等待结束时的状态快照(超时时版本号可能仍等于`since`)
+    pub async fn wait_for_change(&self, since: u64, wait: Duration) -> JobState {
+        let mut rx = self.tx.subscribe();
+        let _ = timeout(wait, rx.wait_for(|state| state.version != since)).await;
+
+        self.snapshot()
+    }
+
+    /// 提交任务
+    ///
+    /// 空闲时直接转换为`Processing`，调用方负责执行任务；
+    /// 否则在队列未满时进入队列
+    ///
+    /// # 参数
+    /// - `ranges`: 要测试的IP段
+    /// - `ips`: IP段包含的IP总数
+    ///
+    /// # 返回值
+    /// 返回提交结果
+    pub fn submit(&self, ranges: Vec<String>, ips: u128) -> Submit {
+        let count = ranges.len();
+        self.transition(|state| {
+            let busy = state.status() == Status::Processing;
+            if busy && state.queue.len() >= self.queue_depth {
+                return None;
+            }
+
+            state.last_id += 1;
+            let mut info = JobInfo::new(state.last_id, count, ips);
+            if !busy {
+                info.start();
+                state.current = Some(info.clone());
+                return Some(Submit::Started(info));
+            }
+
+            state.queue.push_back(QueuedJob {
+                info: info.clone(),
+                ranges,
+            });
+            Some(Submit::Queued(info, state.queue.len()))
+        })
+        .unwrap_or(Submit::Busy)
+    }
+
+    /// 分配任务ID(用于导入的任务，不改变任务状态)
+    pub fn allocate_id(&self) -> u64 {
+        let mut id = 0;
+        self.tx.send_if_modified(|state| {
+            state.last_id += 1;
+            id = state.last_id;
+            false
+        });
+
+        id
+    }
+
+    /// 结束当前任务
+    ///
+    /// 队列中还有任务时开始执行下一个任务并返回，
+    /// 否则状态停留在当前任务的结束状态
+    ///
+    /// # 参数
+    /// - `job`: 已标记结束的当前任务
+    ///
+    /// # 返回值
+    /// 返回下一个要执行的任务，没有时返回None
+    pub fn finish(&self, job: JobInfo) -> Option<QueuedJob> {
+        self.transition(|state| {
+            let current = state
+                .current
+                .as_mut()
+                .filter(|current| current.id == job.id && current.state == Status::Processing)?;
+            *current = job;
+
+            let next = state.queue.pop_front().map(|mut next| {
+                next.info.start();
+                state.current = Some(next.info.clone());
+                next
+            });
+            Some(next)
+        })
+        .flatten()
+    }
+
+    /// 原子地修改状态
+    ///
+    /// 修改函数返回Some时提交修改、递增版本号并通知订阅者；
+    /// 返回None时表示条件不满足，修改函数不应改动状态
+    fn transition<R>(&self, f: impl FnOnce(&mut JobState) -> Option<R>) -> Option<R> {
+        let mut ret = None;
+        self.tx.send_if_modified(|state| {
+            ret = f(state);
+            if ret.is_some() {
+                state.version += 1;
+            }
+            ret.is_some()
+        });
+
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::select::{Failure, FailureKind};
+    use std::sync::Arc;
+
+    /// 测试并发提交时只有一个任务启动，其余进入队列或被拒绝
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_submit() {
+        let jobs = Arc::new(JobMachine::new(3, 0));
+        let barrier = Arc::new(tokio::sync::Barrier::new(32));
+
+        let handles: Vec<_> = (0..32)
+            .map(|i| {
+                let jobs = jobs.clone();
+                let barrier = barrier.clone();
+                tokio::spawn(async move {
+                    barrier.wait().await;
+                    jobs.submit(vec![format!("10.0.{}.0/24", i)], 256)
+                })
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        for handle in handles {
+            results.push(handle.await.unwrap());
+        }
+
+        let started = results
+            .iter()
+            .filter(|r| matches!(r, Submit::Started(_)))
+            .count();
+        let mut queued: Vec<usize> = results
+            .iter()
+            .filter_map(|r| match r {
+                Submit::Queued(_, position) => Some(*position),
+                _ => None,
+            })
+            .collect();
+        queued.sort();
+        assert_eq!(started, 1);
+        assert_eq!(queued, vec![1, 2, 3]);
+        assert_eq!(
+            results.iter().filter(|r| matches!(r, Submit::Busy)).count(),
+            28
+        );
+    }
+
+    /// 测试任务结束后依次取出队列中的任务
+    #[test]
+    fn test_finish_drains_queue() {
+        let jobs = JobMachine::new(1, 7);
+        let Submit::Started(mut job) = jobs.submit(vec!["1.1.1.0/24".to_string()], 256) else {
+            panic!("first job should start");
+        };
+        assert_eq!(job.id, 8);
+        assert!(matches!(
+            jobs.submit(vec!["2.2.2.0/24".to_string()], 256),
+            Submit::Queued(_, 1)
+        ));
+
+        job.finish(Ok(10));
+        let next = jobs.finish(job).unwrap();
+        assert_eq!(next.ranges, vec!["2.2.2.0/24"]);
+        assert_eq!(next.info.id, 9);
+        assert_eq!(jobs.snapshot().status(), Status::Processing);
+
+        let mut job = next.info;
+        job.finish(Err(Failure::new(FailureKind::Timeout, "killed")));
+        assert!(jobs.finish(job.clone()).is_none());
+        let state = jobs.snapshot();
+        assert_eq!(state.status(), Status::TimedOut);
+        assert!(state.current.unwrap().finished_at.is_some());
+
+        // 已结束的任务不能再次结束
+        assert!(jobs.finish(job).is_none());
+        assert!(matches!(jobs.submit(vec![], 0), Submit::Started(_)));
+        assert_eq!(jobs.snapshot().version, 5);
+    }
+
+    /// 测试等待状态变化
+    #[tokio::test]
+    async fn test_wait_for_change() {
+        let jobs = Arc::new(JobMachine::new(1, 0));
+
+        // 版本号已变化时立即返回
+        assert_eq!(
+            jobs.wait_for_change(7, Duration::from_secs(5))
+                .await
+                .version,
+            0
+        );
+
+        // 超时返回原版本号
+        let state = jobs.wait_for_change(0, Duration::from_millis(10)).await;
+        assert_eq!(state.version, 0);
+
+        // 状态变化时唤醒等待者
+        let waiter = {
+            let jobs = jobs.clone();
+            tokio::spawn(async move { jobs.wait_for_change(0, Duration::from_secs(5)).await })
+        };
+        tokio::task::yield_now().await;
+        jobs.submit(vec![], 0);
+        let state = waiter.await.unwrap();
+        assert_eq!(state.version, 1);
+        assert_eq!(state.status(), Status::Processing);
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! 包含服务器核心功能实现：
 //! - `app`: 应用主逻辑
+//! - `job`: 优选任务状态机
 //! - `state`: 共享应用状态管理
 
 pub mod app;
+pub mod job;
 pub mod state;
@@ -5,8 +5,20 @@
 //! - 提供线程安全的状态访问
 //! - 封装配置信息
 
+use crate::catalog::Catalog;
 use crate::configure::AppConfig;
+use crate::model::dns::DnsChangeLog;
+use crate::model::exclusion::Exclusion;
+use crate::model::monitor::MonitorState;
+use crate::model::preset::Preset;
+use crate::server::job::JobMachine;
+use crate::store::JsonStore;
+use crate::store::history::JobHistory;
+use crate::store::measurement::MeasurementStore;
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::info;
 
 /// 应用共享状态容器
 ///
@@ -15,9 +27,25 @@ use std::sync::Arc;
 ///   - 类型: `Arc<AppConfig>`
 ///   - 线程安全: 通过Arc保证
 ///   - 生命周期: 与整个应用相同
+/// - `catalog`: Cloudflare IP段目录(持久化到数据目录)
+/// - `presets`: 按名称索引的IP段预设(持久化到数据目录)
+/// - `exclusions`: IP排除列表(持久化到数据目录)
+/// - `monitor`: 已发布DNS记录的监控状态
+/// - `jobs`: 优选任务状态机(状态、计时和等待队列)
+/// - `job_history`: 已结束的优选任务及其结果(持久化到数据目录)
+/// - `measurements`: 每个IP的历史测量记录(持久化到数据目录)
+/// - `dns_changes`: DNS变更记录及变更ID计数(持久化到数据目录)
 #[derive(Clone)]
 pub struct AppState {
     pub config: Arc<AppConfig>,
+    pub catalog: Arc<JsonStore<Catalog>>,
+    pub presets: Arc<JsonStore<BTreeMap<String, Preset>>>,
+    pub exclusions: Arc<JsonStore<Vec<Exclusion>>>,
+    pub monitor: Arc<RwLock<MonitorState>>,
+    pub jobs: Arc<JobMachine>,
+    pub job_history: Arc<JobHistory>,
+    pub measurements: Arc<MeasurementStore>,
+    pub dns_changes: Arc<JsonStore<DnsChangeLog>>,
 }
 
 impl AppState {
@@ -30,7 +58,44 @@ impl AppState {
     /// - 成功: 返回初始化好的AppState
     /// - 失败: 返回错误信息
     pub async fn new(config: AppConfig) -> anyhow::Result<Self> {
+        let catalog: JsonStore<Catalog> =
+            JsonStore::open(config.storage.path("catalog.json")).await?;
+        // 升级后内置列表比之前刷新保存的列表更新时，改用内置列表
+        let outdated = catalog.read().await.is_outdated();
+        if outdated {
+            info!("saved ip ranges are older than the builtin list, using the builtin list");
+            catalog
+                .update(|current| *current = Catalog::default())
+                .await?;
+        }
+        let presets = JsonStore::open(config.storage.path("presets.json")).await?;
+        let exclusions = JsonStore::open(config.storage.path("exclusions.json")).await?;
+        let job_history = JobHistory::open(
+            config.storage.path("jobs.json"),
+            config.storage.path("results"),
+            config.job.history_size,
+        )
+        .await?;
+        let measurements = MeasurementStore::open(
+            config.storage.path("measurements.jsonl"),
+            config.history.retention(),
+        )
+        .await?;
+        let dns_changes = JsonStore::open(config.storage.path("dns_changes.json")).await?;
+        // 任务ID在重启后继续递增，保证历史任务ID唯一
+        let jobs = JobMachine::new(config.job.queue_depth, job_history.last_id().await);
+
         let config = Arc::new(config);
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            catalog: Arc::new(catalog),
+            presets: Arc::new(presets),
+            exclusions: Arc::new(exclusions),
+            monitor: Arc::new(RwLock::new(MonitorState::default())),
+            jobs: Arc::new(jobs),
+            job_history: Arc::new(job_history),
+            measurements: Arc::new(measurements),
+            dns_changes: Arc::new(dns_changes),
+        })
     }
 }
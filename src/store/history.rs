@@ -0,0 +1,266 @@
+//! 优选任务历史存储模块
+//!
+//! 负责:
+//! - 保存已结束任务的信息(任务索引文件)
+//! - 保存成功任务的原始结果文件(每个任务一个CSV文件)
+//! - 超出保留数量时删除最早的任务及其结果文件
+//! - 保存已分配的最大任务ID，重启后不复用已清理任务的ID
+
+use crate::model::select::{JobInfo, Status};
+use crate::store::JsonStore;
+use anyhow::Context;
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use tokio::fs;
+use tracing::warn;
+
+/// 任务索引
+///
+/// # 字段
+/// - `last_id`: 已记录的最大任务ID，旧任务被清理后仍保留，保证ID不被复用
+/// - `jobs`: 已结束的任务，按结束顺序排列
+#[derive(Debug, Clone, Default, Serialize)]
+struct JobIndex {
+    last_id: u64,
+    jobs: Vec<JobInfo>,
+}
+
+/// 当前版本的任务索引文件格式
+#[derive(Deserialize)]
+struct IndexFile {
+    last_id: u64,
+    jobs: Vec<JobInfo>,
+}
+
+/// 兼容旧版本只保存任务列表的索引文件，此时按保留的任务计算最大任务ID
+impl<'de> Deserialize<'de> for JobIndex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct IndexVisitor;
+
+        impl<'de> Visitor<'de> for IndexVisitor {
+            type Value = JobIndex;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a job index or a list of jobs")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<JobIndex, A::Error> {
+                let jobs = Vec::<JobInfo>::deserialize(SeqAccessDeserializer::new(seq))?;
+                Ok(JobIndex {
+                    last_id: jobs.iter().map(|job| job.id).max().unwrap_or(0),
+                    jobs,
+                })
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<JobIndex, A::Error> {
+                let file = IndexFile::deserialize(MapAccessDeserializer::new(map))?;
+                Ok(JobIndex {
+                    last_id: file.last_id,
+                    jobs: file.jobs,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(IndexVisitor)
+    }
+}
+
+/// 优选任务历史
+///
+/// # 字段
+/// - `index`: 任务索引
+/// - `dir`: 结果文件目录
+/// - `capacity`: 最多保留的任务数量
+pub struct JobHistory {
+    index: JsonStore<JobIndex>,
+    dir: PathBuf,
+    capacity: usize,
+}
+
+impl JobHistory {
+    /// 打开任务历史
+    ///
+    /// # 参数
+    /// - `index`: 任务索引文件路径
+    /// - `dir`: 结果文件目录
+    /// - `capacity`: 最多保留的任务数量
+    ///
+    /// # 返回值
+    /// - 成功: 返回加载好的任务历史
+    /// - 失败: 索引文件存在但无法读取或解析
+    pub async fn open(
+        index: impl Into<PathBuf>,
+        dir: impl Into<PathBuf>,
+        capacity: usize,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            index: JsonStore::open(index).await?,
+            dir: dir.into(),
+            capacity,
+        })
+    }
+
+    /// 记录已结束的任务
+    ///
+    /// 先写结果文件再写索引，保证索引中的成功任务都有结果文件
+    ///
+    /// # 参数
+    /// - `job`: 已结束的任务
+    /// - `results`: 结果文件内容(只有成功的任务才有)
+    ///
+    /// # 返回值
+    /// - 成功: Ok(())
+    /// - 失败: 写入结果文件或索引失败
+    pub async fn record(&self, job: JobInfo, results: Option<&str>) -> anyhow::Result<()> {
+        if let Some(results) = results {
+            fs::create_dir_all(&self.dir).await?;
+            let path = self.result_path(job.id);
+            fs::write(&path, results)
+                .await
+                .with_context(|| format!("写入结果文件失败: {}", path.display()))?;
+        }
+
+        let capacity = self.capacity;
+        let removed = self
+            .index
+            .update(|index| {
+                index.last_id = index.last_id.max(job.id);
+                index.jobs.push(job);
+                let overflow = index.jobs.len().saturating_sub(capacity);
+                index.jobs.drain(..overflow).collect::<Vec<_>>()
+            })
+            .await?;
+
+        for job in removed {
+            if let Err(e) = fs::remove_file(self.result_path(job.id)).await
+                && e.kind() != ErrorKind::NotFound
+            {
+                warn!("Failed to remove results of job {}: {}", job.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 按ID获取任务
+    pub async fn get(&self, id: u64) -> Option<JobInfo> {
+        self.index
+            .read()
+            .await
+            .jobs
+            .iter()
+            .find(|job| job.id == id)
+            .cloned()
+    }
+
+    /// 获取最近一次成功的任务
+    pub async fn latest_success(&self) -> Option<JobInfo> {
+        self.index
+            .read()
+            .await
+            .jobs
+            .iter()
+            .rev()
+            .find(|job| job.state == Status::Success)
+            .cloned()
+    }
+
+    /// 获取最近的成功任务(从新到旧排列)
+    ///
+    /// # 参数
+    /// - `n`: 最多返回的任务数量
+    pub async fn recent_success(&self, n: usize) -> Vec<JobInfo> {
+        self.index
+            .read()
+            .await
+            .jobs
+            .iter()
+            .rev()
+            .filter(|job| job.state == Status::Success)
+            .take(n)
+            .cloned()
+            .collect()
+    }
+
+    /// 已记录的最大任务ID(包括已清理的任务)，用于重启后继续分配任务ID
+    pub async fn last_id(&self) -> u64 {
+        self.index.read().await.last_id
+    }
+
+    /// 读取任务的结果文件
+    ///
+    /// # 参数
+    /// - `id`: 任务ID
+    ///
+    /// # 返回值
+    /// - 成功: 返回结果文件内容
+    /// - 失败: 结果文件不存在或无法读取
+    pub async fn results(&self, id: u64) -> anyhow::Result<String> {
+        let path = self.result_path(id);
+        fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("读取结果文件失败: {}", path.display()))
+    }
+
+    /// 任务结果文件路径
+    fn result_path(&self, id: u64) -> PathBuf {
+        self.dir.join(format!("{}.csv", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configure::AppConfig;
+    use crate::configure::storage::StorageConfig;
+
+    /// 在独立数据目录中打开任务历史
+    async fn open(storage: &StorageConfig, capacity: usize) -> JobHistory {
+        JobHistory::open(storage.path("jobs.json"), storage.path("results"), capacity)
+            .await
+            .unwrap()
+    }
+
+    /// 测试超出容量时删除最早结束的任务及结果文件，重新打开后最大任务ID仍保留
+    #[tokio::test]
+    async fn test_last_id_survives_trim() {
+        let storage = AppConfig::test("").storage;
+        let history = open(&storage, 1).await;
+
+        // 导入的任务先于正在执行的任务结束
+        history
+            .record(JobInfo::imported(5, 1), Some("results"))
+            .await
+            .unwrap();
+        history.record(JobInfo::new(4, 1, 1), None).await.unwrap();
+        assert!(history.get(5).await.is_none());
+        assert!(history.results(5).await.is_err());
+        assert_eq!(history.last_id().await, 5);
+
+        let history = open(&storage, 1).await;
+        assert_eq!(history.last_id().await, 5);
+        assert_eq!(history.get(4).await.unwrap().id, 4);
+    }
+
+    /// 测试读取旧版本只保存任务列表的索引文件
+    #[tokio::test]
+    async fn test_legacy_index() {
+        let storage = AppConfig::test("").storage;
+        fs::create_dir_all(&storage.dir).await.unwrap();
+        let jobs = vec![JobInfo::imported(3, 1), JobInfo::imported(2, 1)];
+        fs::write(
+            storage.path("jobs.json"),
+            serde_json::to_vec(&jobs).unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let history = open(&storage, 10).await;
+        assert_eq!(history.last_id().await, 3);
+        assert_eq!(history.latest_success().await.unwrap().id, 2);
+    }
+}
@@ -0,0 +1,176 @@
+//! IP测量历史存储模块
+//!
+//! 以JSON Lines格式追加保存每一次测量:
+//! - 每行一条测量记录，追加写入，不需要整体重写
+//! - 查询时逐行读取并按IP和时间过滤
+//! - 定期整理文件，删除超过保留时长的记录
+
+use crate::model::history::Measurement;
+use anyhow::Context;
+use chrono::{DateTime, Duration, Local};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+use tracing::info;
+
+/// 两次整理之间的最短间隔
+const COMPACT_INTERVAL: Duration = Duration::hours(24);
+
+/// 测量历史存储
+///
+/// # 字段
+/// - `path`: 存储文件路径
+/// - `retention`: 测量记录保留时长
+/// - `compacted_at`: 最近一次整理时间(同时用于串行化写入)
+pub struct MeasurementStore {
+    path: PathBuf,
+    retention: Duration,
+    compacted_at: Mutex<DateTime<Local>>,
+}
+
+impl MeasurementStore {
+    /// 打开存储文件，并删除已过期的记录
+    ///
+    /// # 参数
+    /// - `path`: 存储文件路径
+    /// - `retention`: 测量记录保留时长
+    ///
+    /// # 返回值
+    /// - 成功: 返回存储
+    /// - 失败: 文件存在但无法读取或重写
+    pub async fn open(path: impl Into<PathBuf>, retention: Duration) -> anyhow::Result<Self> {
+        let store = Self {
+            path: path.into(),
+            retention,
+            compacted_at: Mutex::new(Local::now()),
+        };
+        store.compact().await?;
+
+        Ok(store)
+    }
+
+    /// 追加测量记录
+    ///
+    /// 距上次整理超过一天时先整理文件
+    ///
+    /// # 参数
+    /// - `measurements`: 要追加的测量记录
+    ///
+    /// # 返回值
+    /// - 成功: Ok(())
+    /// - 失败: 写入文件失败
+    pub async fn append(&self, measurements: &[Measurement]) -> anyhow::Result<()> {
+        if measurements.is_empty() {
+            return Ok(());
+        }
+
+        let mut compacted_at = self.compacted_at.lock().await;
+        if Local::now() - *compacted_at > COMPACT_INTERVAL {
+            self.compact().await?;
+            *compacted_at = Local::now();
+        }
+
+        let mut lines = String::new();
+        for measurement in measurements {
+            lines.push_str(&serde_json::to_string(measurement)?);
+            lines.push('\n');
+        }
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(lines.as_bytes())
+            .await
+            .with_context(|| format!("写入测量历史失败: {}", self.path.display()))
+    }
+
+    /// 查询单个IP的测量记录
+    ///
+    /// # 参数
+    /// - `ip`: IP地址
+    /// - `from`: 起始时间(包含)
+    /// - `to`: 结束时间(包含)
+    ///
+    /// # 返回值
+    /// - 成功: 返回按写入顺序排列的测量记录
+    /// - 失败: 文件无法读取
+    pub async fn query(
+        &self,
+        ip: &str,
+        from: Option<DateTime<Local>>,
+        to: Option<DateTime<Local>>,
+    ) -> anyhow::Result<Vec<Measurement>> {
+        let needle = format!("\"ip\":\"{}\"", ip);
+        Ok(self
+            .scan(|line| line.contains(&needle))
+            .await?
+            .into_iter()
+            .filter(|m| m.ip == ip)
+            .filter(|m| from.is_none_or(|from| m.measured_at >= from))
+            .filter(|m| to.is_none_or(|to| m.measured_at <= to))
+            .collect())
+    }
+
+    /// 删除超过保留时长的记录
+    async fn compact(&self) -> anyhow::Result<()> {
+        // 保留时长超过可表示的时间范围时没有过期记录
+        let Some(cutoff) = Local::now().checked_sub_signed(self.retention) else {
+            return Ok(());
+        };
+        let all = self.scan(|_| true).await?;
+        let kept: Vec<&Measurement> = all.iter().filter(|m| m.measured_at >= cutoff).collect();
+        if kept.len() == all.len() {
+            return Ok(());
+        }
+
+        let mut lines = String::new();
+        for measurement in &kept {
+            lines.push_str(&serde_json::to_string(measurement)?);
+            lines.push('\n');
+        }
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, lines).await?;
+        fs::rename(&tmp, &self.path)
+            .await
+            .with_context(|| format!("整理测量历史失败: {}", self.path.display()))?;
+        info!(
+            "measurement history compacted: {} expired records removed",
+            all.len() - kept.len()
+        );
+
+        Ok(())
+    }
+
+    /// 逐行读取文件，解析通过预过滤的行(无法解析的行会被跳过)
+    async fn scan(&self, filter: impl Fn(&str) -> bool) -> anyhow::Result<Vec<Measurement>> {
+        let file = match fs::File::open(&self.path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("读取测量历史失败: {}", self.path.display()));
+            }
+        };
+
+        let mut measurements = Vec::new();
+        let mut lines = BufReader::new(file).lines();
+        while let Some(line) = lines.next_line().await? {
+            if !filter(&line) {
+                continue;
+            }
+            if let Ok(measurement) = serde_json::from_str(&line) {
+                measurements.push(measurement);
+            }
+        }
+
+        Ok(measurements)
+    }
+}
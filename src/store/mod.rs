@@ -0,0 +1,97 @@
+//! 持久化存储模块
+//!
+//! 提供基于JSON文件的简单持久化存储：
+//! - 启动时从文件加载数据，文件不存在时使用默认值
+//! - 每次修改后整体写回文件(先写临时文件再重命名，避免写坏)
+//! - 优选任务历史及结果文件(history)
+//! - 单个IP的测量历史(measurement)
+
+use anyhow::Context;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+pub mod history;
+pub mod measurement;
+
+/// JSON文件存储
+///
+/// # 字段
+/// - `path`: 存储文件路径
+/// - `data`: 内存中的数据副本
+pub struct JsonStore<T> {
+    path: PathBuf,
+    data: RwLock<T>,
+}
+
+impl<T> JsonStore<T>
+where
+    T: Serialize + DeserializeOwned + Default + Clone,
+{
+    /// 打开存储文件
+    ///
+    /// # 参数
+    /// - `path`: 存储文件路径
+    ///
+    /// # 返回值
+    /// - 成功: 返回加载好的存储
+    /// - 失败: 文件存在但无法读取或解析
+    pub async fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let data = match fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("解析存储文件失败: {}", path.display()))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => T::default(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("读取存储文件失败: {}", path.display()));
+            }
+        };
+
+        Ok(Self {
+            path,
+            data: RwLock::new(data),
+        })
+    }
+
+    /// 获取数据的只读引用
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.data.read().await
+    }
+
+    /// 修改数据并写回文件
+    ///
+    /// 修改在数据副本上进行，只有写回成功后才会替换内存中的数据
+    ///
+    /// # 参数
+    /// - `f`: 修改函数，其返回值会原样返回
+    ///
+    /// # 返回值
+    /// - 成功: 返回修改函数的返回值
+    /// - 失败: 写回文件失败
+    pub async fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> anyhow::Result<R> {
+        let mut data = self.data.write().await;
+        let mut next = data.clone();
+        let ret = f(&mut next);
+        save(&self.path, &next).await?;
+        *data = next;
+
+        Ok(ret)
+    }
+}
+
+/// 将数据序列化后写入文件
+async fn save<T: Serialize>(path: &Path, data: &T) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+
+    let json = serde_json::to_vec_pretty(data)?;
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, json).await?;
+    fs::rename(&tmp, path)
+        .await
+        .with_context(|| format!("写入存储文件失败: {}", path.display()))
+}